@@ -1,4 +1,6 @@
+use node_template_runtime::{AccountId, MultiAccount};
 use sc_cli::RunCmd;
+use sp_core::crypto::Ss58Codec;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -50,4 +52,55 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Multi-account utilities.
+	#[command(subcommand)]
+	MultiAccount(MultiAccountSubcommand),
+}
+
+/// Multi-account utilities that don't need a running node.
+#[derive(Debug, clap::Subcommand)]
+pub enum MultiAccountSubcommand {
+	/// Derive the SS58 address of a multi-account from its signatories and threshold.
+	Derive(DeriveCmd),
+}
+
+impl MultiAccountSubcommand {
+	/// Run the selected multi-account utility.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		match self {
+			MultiAccountSubcommand::Derive(cmd) => cmd.run(),
+		}
+	}
+}
+
+/// The `multi-account derive` command.
+#[derive(Debug, clap::Parser)]
+pub struct DeriveCmd {
+	/// SS58 address of a signatory. Pass it once for every signatory, in any order.
+	#[arg(long = "signatory", required = true)]
+	pub signatories: Vec<String>,
+
+	/// Number of approvals needed to dispatch a call.
+	#[arg(long)]
+	pub threshold: u16,
+}
+
+impl DeriveCmd {
+	/// Derive the address with the same code the runtime uses and print it.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let signatories = self
+			.signatories
+			.iter()
+			.map(|address| {
+				AccountId::from_ss58check(address).map_err(|err| {
+					sc_cli::Error::Input(format!("invalid signatory {}: {:?}", address, err))
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let id = MultiAccount::derive_account_id(signatories, self.threshold)
+			.map_err(|err| sc_cli::Error::Input(format!("{:?}", err)))?;
+		println!("{}", id.to_ss58check());
+		Ok(())
+	}
 }
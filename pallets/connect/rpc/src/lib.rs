@@ -5,11 +5,8 @@
 mod tests;
 
 pub use connect_runtime_api::ConnectApi;
-use jsonrpsee::{
-	core::{Error as JsonRpseeError, RpcResult},
-	proc_macros::rpc,
-	types::error::{CallError, ErrorObject},
-};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use rpc_errors::{block_hash, Error};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::Block as BlockT;
@@ -51,21 +48,7 @@ where
 {
 	fn total_registered(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<u32> {
 		let api = self.client.runtime_api();
-		let block_hash = at.unwrap_or_else(||
-			// If the block hash is not supplied assume the best block.
-			self.client.info().best_hash);
-		api.total_registered(block_hash).map_err(runtime_error_into_rpc_err)
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		Ok(api.total_registered(block_hash).map_err(Error::runtime_api)?)
 	}
 }
-
-const RUNTIME_ERROR: i32 = 1;
-
-/// Converts a runtime trap into an RPC error.
-fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
-	CallError::Custom(ErrorObject::owned(
-		RUNTIME_ERROR,
-		"Runtime error",
-		Some(format!("{:?}", err)),
-	))
-	.into()
-}
@@ -48,6 +48,13 @@ type BalanceOf<T> =
 // Lock id for locking the minimum lockable amount.
 const LOCK_ID: LockIdentifier = *b"LOCKEDUP";
 
+/// Reads how much an account has locked under a lock identifier. `LockableCurrency` can only set
+/// locks, so the runtime implements this with the locks of its balances pallet.
+pub trait InspectLocks<AccountId, Balance> {
+	/// The amount `who` has locked under `id`, zero if there is no such lock.
+	fn locked(id: LockIdentifier, who: &AccountId) -> Balance;
+}
+
 // Hex values for the gradient - right, left.
 type Gradient = (Vec<u8>, Vec<u8>);
 
@@ -91,6 +98,8 @@ pub mod pallet {
 		type MaxNameLength: Get<u32>;
 		/// Randomness!
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+		/// Reads the lock placed on registration back
+		type Locks: InspectLocks<Self::AccountId, BalanceOf<Self>>;
 	}
 
 	/// User Metadata - note the traits, especially the Encode/Decode which allow for SCALE encoding
@@ -241,6 +250,20 @@ pub mod pallet {
 	/// Note how you can also simply utilize the Pallet struct as per normal to declare helper
 	/// functions, or anything helpful in the context of the pallet.
 	impl<T: Config> Pallet<T> {
+		/// Whether `who` is a registered user whose registration lock still holds the minimum
+		/// lockable amount. A lock can outlast the funds under it, e.g. after a slash, so only the
+		/// part of the lock the balance covers counts.
+		pub fn is_verified(who: &T::AccountId) -> bool {
+			<RegisteredUsers<T>>::contains_key(who) &&
+				T::Locks::locked(LOCK_ID, who).min(T::Currency::total_balance(who)) >=
+					T::MinimumLockableAmount::get()
+		}
+
+		/// The name `who` registered with, if it is a registered user.
+		pub fn name_of(who: &T::AccountId) -> Option<Vec<u8>> {
+			<RegisteredUsers<T>>::get(who).map(|user| user.name.into_inner())
+		}
+
 		/// Generates hex values for a gradient profile picture
 		pub fn generate_hex_values(random_value: T::Hash) -> Gradient {
 			let hex = hex::encode(random_value);
@@ -2,7 +2,7 @@ use crate as pallet_connect;
 
 use frame_support::{
 	parameter_types,
-	traits::{ConstU16, ConstU64},
+	traits::{ConstU16, ConstU64, LockIdentifier},
 };
 use sp_core::{ConstU128, H256};
 use sp_runtime::{
@@ -83,6 +83,13 @@ parameter_types! {
 	pub const MaxBioLength: u32 = 200;
 	pub const MaxNameLength: u32 = 10;
 }
+pub struct BalanceLocks;
+impl pallet_connect::InspectLocks<u64, Balance> for BalanceLocks {
+	fn locked(id: LockIdentifier, who: &u64) -> Balance {
+		Balances::locks(who).iter().find(|lock| lock.id == id).map_or(0, |lock| lock.amount)
+	}
+}
+
 impl pallet_connect::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
@@ -91,6 +98,7 @@ impl pallet_connect::Config for Test {
 	type MinimumLockableAmount = ConstU128<10>;
 	type MaxNameLength = MaxNameLength;
 	type Randomness = TestRandomness<Self>;
+	type Locks = BalanceLocks;
 }
 
 // Build genesis storage according to the mock runtime.
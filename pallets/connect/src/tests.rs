@@ -62,3 +62,21 @@ fn balance_too_low() {
 		);
 	});
 }
+
+#[test]
+fn only_users_holding_the_lock_are_verified() {
+	use frame_support::traits::{Currency, LockableCurrency};
+
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 1, 20));
+		assert!(!Connect::is_verified(&1));
+		assert_ok!(Connect::register(RuntimeOrigin::signed(1), b"polkadot".to_vec(), vec![]));
+		assert!(Connect::is_verified(&1));
+
+		// the balance alone is not enough once the lock is gone
+		Balances::remove_lock(crate::LOCK_ID, &1);
+		assert_eq!(Balances::total_balance(&1), 20);
+		assert!(!Connect::is_verified(&1));
+	});
+}
@@ -3,24 +3,265 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
-use codec::Codec;
-    // create rpc call to check number of signed sig for a call hash ❌ 
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{traits::NumberFor, DispatchResult, RuntimeDebug};
+use sp_weights::Weight;
+    // create rpc call to check number of signed sig for a call hash ❌
 	// create rpc for getting signatories for an account ❌
 	// create rpc for getting the number of threshold required for a multi account ❌
 type CallHash = [u8; 32];
 
+/// Why a multi-account runtime API call could not be answered.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum MultiAccountApiError {
+	/// No multi-account is registered under the given id.
+	UnknownAccount,
+	/// The multi-account has no pending call with the given hash.
+	CallNotFound,
+	/// The bytes are not a `RuntimeCall` of this runtime.
+	InvalidCall,
+	/// The signatories and threshold do not form a valid multi-account.
+	InvalidSignatories,
+}
+
+/// What would happen if a call were dispatched by a multi-account right now.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct DryRunInfo<Balance> {
+	/// The result of dispatching the call with the multi-account as origin.
+	pub result: DispatchResult,
+	/// The weight the call actually consumed.
+	pub actual_weight: Weight,
+	/// Estimated fee of the `approve_or_dispatch_call` that would dispatch the call.
+	pub partial_fee: Balance,
+	/// SCALE encoded `RuntimeEvent`s emitted by the call.
+	pub events: Vec<Vec<u8>>,
+}
+
+/// Everything a UI shows about a multi-account, fetched in a single call.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AccountView<AccountId, Balance, BlockNumber> {
+	/// The sorted signatories of the account.
+	pub signatories: Vec<AccountId>,
+	/// Approvals needed before a call is dispatched.
+	pub threshold: u32,
+	/// Free balance of the multi-account.
+	pub balance: Balance,
+	/// Nonce of the multi-account.
+	pub nonce: u32,
+	/// Calls that were proposed but have not reached the threshold yet.
+	pub pending_proposals: u32,
+	/// The rules the account set for itself on top of its threshold.
+	pub policy: AccountPolicy<Balance, BlockNumber>,
+}
+
+/// The rules a multi-account runs under besides its threshold.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AccountPolicy<Balance, BlockNumber> {
+	/// Guardians that can freeze the account and veto its proposals.
+	pub guardians: u32,
+	/// Whether the guardians froze the account.
+	pub frozen: bool,
+	/// What the account still pays of its signatories' approval fees.
+	pub fee_budget: Balance,
+	/// Blocks without activity after which the heirs can claim the account, if it has heirs.
+	pub inactivity_period: Option<BlockNumber>,
+	/// Blocks after its proposal until a call needs fewer approvals, if the threshold decays.
+	pub decay_after: Option<BlockNumber>,
+}
+
+/// A recurring transfer the pallet makes from a multi-account.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ScheduleView<AccountId, Balance, BlockNumber> {
+	/// Index to cancel the schedule with.
+	pub index: u32,
+	/// Receiver of the payments.
+	pub dest: AccountId,
+	/// Amount of every payment.
+	pub amount: Balance,
+	/// Blocks between two payments.
+	pub interval: BlockNumber,
+	/// Payments left to make, `None` if the transfer repeats until it is cancelled.
+	pub remaining: Option<u32>,
+	/// The block the next payment is made in.
+	pub next: BlockNumber,
+}
+
+/// A call that was proposed and has not reached its threshold yet.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProposalView<AccountId> {
+	/// Hash of the SCALE encoded call.
+	pub call_hash: CallHash,
+	/// The signatory that proposed the call.
+	pub proposer: AccountId,
+	/// Signatories that approved the call so far, including the proposer.
+	pub approvals: Vec<AccountId>,
+	/// Whether the call has to be proposed again after a runtime upgrade.
+	pub stale: bool,
+	/// Title the proposer gave the call, empty if it set no metadata.
+	pub title: Vec<u8>,
+	/// Hash of a description kept off chain.
+	pub description_hash: Option<[u8; 32]>,
+	/// Labels the proposer gave the call.
+	pub tags: Vec<Vec<u8>>,
+}
+
+/// A signatory of a multi-account with the identity it registered with Connect.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SignatoryView<AccountId> {
+	/// The signatory.
+	pub account: AccountId,
+	/// The name the signatory registered with Connect, if it is a registered user.
+	pub name: Option<Vec<u8>>,
+	/// Whether the signatory can currently propose and approve calls.
+	pub verified: bool,
+}
+
+/// A pending vote to replace a signatory whose key was lost.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RecoveryView<AccountId, BlockNumber> {
+	/// The signatory whose key was lost.
+	pub lost: AccountId,
+	/// The key that takes over the seat.
+	pub new: AccountId,
+	/// The signatories that voted for the recovery.
+	pub votes: Vec<AccountId>,
+	/// Votes needed to finalize the recovery.
+	pub votes_needed: u16,
+	/// The first block the recovery can be finalized in.
+	pub delay_end: BlockNumber,
+}
+
+/// A budget compartment of a multi-account, controlled by the same signatories.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SubAccountView<AccountId, Balance> {
+	/// Index the sub-account is derived from.
+	pub index: u16,
+	/// Address of the sub-account.
+	pub address: AccountId,
+	/// Free balance of the sub-account.
+	pub balance: Balance,
+}
+
+/// Why a proposal with enough approvals has not been dispatched.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", tag = "type"))]
+pub enum BlockedReason<BlockNumber> {
+	/// The prerequisite proposal has not been dispatched successfully yet.
+	PrerequisiteNotExecuted { prerequisite: CallHash },
+	/// The proposal can't be dispatched before `not_before`.
+	TooEarly { not_before: BlockNumber },
+	/// The proposal could only be dispatched up to `not_after`.
+	TooLate { not_after: BlockNumber },
+}
+
+/// A multi-account event of a single block, as streamed by `multi_subscribeProposals`.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", tag = "type"))]
+pub enum AccountEvent<AccountId> {
+	/// A signatory proposed a new call.
+	Proposed { proposer: AccountId, call_hash: CallHash },
+	/// A signatory approved a call, including the proposer's own approval.
+	Approved { signer: AccountId, call_hash: CallHash },
+	/// A call reached its threshold and was dispatched.
+	Executed { call_hash: CallHash, result: DispatchResult },
+	/// A commitment was not revealed before its deadline, or a call outlived its block window
+	/// or the proposal lifetime, and it was removed.
+	Expired { call_hash: CallHash },
+	/// A call was withdrawn by its proposer, vetoed by a guardian, went stale with a runtime
+	/// upgrade or was dropped when the account was inherited or closed.
+	Removed { call_hash: CallHash },
+}
+
 sp_api::decl_runtime_apis! {
 	/// This trait contains all the Api's that can be called into from the runtime
 	/// into our pallet. To read or perform certain state actions in our blockchain
-	pub trait MultiAccountApi<AccountId>
-    where AccountId: Codec {
+	///
+	/// Version 2 added `dry_run`, `derive_account_id`, `account_events`, `get_call_data` and
+	/// `account_info` and made the version 1 methods return a `MultiAccountApiError` instead of
+	/// `None`, version 3 added `schedules`, version 4
+	/// `pending_proposals`, version 5 `blocked_reason`, version 6 `sub_accounts`, version 7
+	/// `signatories`, version 8 `recoveries`, version 9 `effective_threshold`.
+	#[api_version(9)]
+	pub trait MultiAccountApi<AccountId, Balance>
+    where AccountId: Codec, Balance: Codec {
 		/// get the number of accounts that have approved a particular call hash
-        fn get_approvals_for_call(id: AccountId, call: CallHash ) -> Option<u32>;
-        // /// get the signatories for an account
+        #[changed_in(2)]
+        fn get_approvals_for_call(id: AccountId, call: CallHash) -> Option<u32>;
+        fn get_approvals_for_call(id: AccountId, call: CallHash) -> Result<u32, MultiAccountApiError>;
+        /// get the signatories for an account
+        #[changed_in(2)]
         fn get_signatories_for_account(id: AccountId) -> Option<Vec<AccountId>>;
+        fn get_signatories_for_account(id: AccountId) -> Result<Vec<AccountId>, MultiAccountApiError>;
         /// get the threshold required for a call to pass
+        #[changed_in(2)]
         fn get_threshold_for_account(id: AccountId) -> Option<u32>;
+        fn get_threshold_for_account(id: AccountId) -> Result<u32, MultiAccountApiError>;
         /// get the accounts that has approved a particular call
+        #[changed_in(2)]
         fn get_approval_accounts_for_call(id: AccountId, call: CallHash) -> Option<Vec<AccountId>>;
+        fn get_approval_accounts_for_call(id: AccountId, call: CallHash) -> Result<Vec<AccountId>, MultiAccountApiError>;
+		/// simulate dispatching a SCALE encoded `RuntimeCall` as the multi-account. Nothing is
+		/// persisted.
+		#[api_version(2)]
+		fn dry_run(id: AccountId, call: Vec<u8>) -> Result<DryRunInfo<Balance>, MultiAccountApiError>;
+		/// derive the multi-account id for a set of signatories in any order and a threshold.
+		#[api_version(2)]
+		fn derive_account_id(signatories: Vec<AccountId>, threshold: u16) -> Result<AccountId, MultiAccountApiError>;
+		/// the events the multi-account emitted in the block this is called at
+		#[api_version(2)]
+		fn account_events(id: AccountId) -> Vec<AccountEvent<AccountId>>;
+		/// get the SCALE encoded call behind a pending call hash. `None` if the call is pending
+		/// but its preimage was too large to be stored.
+		#[api_version(2)]
+		fn get_call_data(id: AccountId, call: CallHash) -> Result<Option<Vec<u8>>, MultiAccountApiError>;
+		/// get the signatories, threshold, balance, nonce and pending proposal count of an
+		/// account in one call. Returns `None` if no multi-account is registered under `id`.
+		#[api_version(2)]
+		fn account_info(id: AccountId) -> Option<AccountView<AccountId, Balance, NumberFor<Block>>>;
+		/// get the recurring transfers of an account
+		#[api_version(3)]
+		fn schedules(id: AccountId) -> Vec<ScheduleView<AccountId, Balance, NumberFor<Block>>>;
+		/// get the calls of an account that are waiting for approvals, with their metadata
+		#[api_version(4)]
+		fn pending_proposals(id: AccountId) -> Vec<ProposalView<AccountId>>;
+		/// get why a pending call can't be dispatched right now because of its prerequisite or
+		/// block window. `None` if nothing but missing approvals holds it back.
+		#[api_version(5)]
+		fn blocked_reason(id: AccountId, call: CallHash) -> Result<Option<BlockedReason<NumberFor<Block>>>, MultiAccountApiError>;
+		/// get the sub-accounts an account dispatched calls from or that hold funds, with their
+		/// balances
+		#[api_version(6)]
+		fn sub_accounts(id: AccountId) -> Vec<SubAccountView<AccountId, Balance>>;
+		/// get the signatories of an account with their Connect names
+		#[api_version(7)]
+		fn signatories(id: AccountId) -> Result<Vec<SignatoryView<AccountId>>, MultiAccountApiError>;
+		/// get the pending recoveries of lost signatory keys of an account
+		#[api_version(8)]
+		fn recoveries(id: AccountId) -> Vec<RecoveryView<AccountId, NumberFor<Block>>>;
+		/// get the approvals a pending call needs now, after the threshold decay of its account
+		#[api_version(9)]
+		fn effective_threshold(id: AccountId, call: CallHash) -> Result<u32, MultiAccountApiError>;
 	}
 }
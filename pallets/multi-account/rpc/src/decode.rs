@@ -0,0 +1,218 @@
+//! Decoding of SCALE encoded runtime calls into JSON with the help of the runtime metadata.
+//!
+//! Approvers only ever see a call hash on chain. The functions in this module walk the type
+//! registry of the runtime the call was made for, so the RPC can show the pallet, call and named
+//! arguments instead. Accounts are rendered as SS58 addresses and 128 bit integers, which is what
+//! balances are in this runtime, as decimal strings so that JavaScript clients don't lose
+//! precision.
+
+use codec::{Compact, Decode};
+use frame_metadata::{v14::RuntimeMetadataV14, RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{json, Map, Value};
+use sp_core::{
+	crypto::{AccountId32, Ss58Codec},
+	hexdisplay::HexDisplay,
+};
+
+/// How deeply types may nest inside a call. Calls of this runtime stay far below it, while the
+/// bytes passed to `multi_decodeCall` could otherwise nest `RuntimeCall`s deep enough to overflow
+/// the stack.
+const MAX_DEPTH: u32 = 64;
+
+/// Decode the opaque metadata returned by the `Metadata` runtime API.
+pub fn metadata_from_bytes(bytes: &[u8]) -> Result<RuntimeMetadataV14, String> {
+	let prefixed = RuntimeMetadataPrefixed::decode(&mut &bytes[..])
+		.map_err(|err| format!("invalid metadata: {}", err))?;
+	match prefixed.1 {
+		RuntimeMetadata::V14(metadata) => Ok(metadata),
+		other => Err(format!("unsupported metadata version {}", other.version())),
+	}
+}
+
+/// Decode a `RuntimeCall` into `{ "pallet": .., "call": .., "args": { .. } }`.
+pub fn decode_call(metadata: &RuntimeMetadataV14, call: &[u8]) -> Result<Value, String> {
+	let input = &mut &call[..];
+	let pallet_index = u8::decode(input).map_err(|err| err.to_string())?;
+	let pallet = metadata
+		.pallets
+		.iter()
+		.find(|pallet| pallet.index == pallet_index)
+		.ok_or_else(|| format!("no pallet with index {}", pallet_index))?;
+	let calls = pallet.calls.as_ref().ok_or_else(|| format!("{} has no calls", pallet.name))?;
+	let variants = match &resolve(&metadata.types, calls.ty.id)?.type_def {
+		TypeDef::Variant(call) => &call.variants,
+		_ => return Err(format!("the call type of {} is not an enum", pallet.name)),
+	};
+	let call_index = u8::decode(input).map_err(|err| err.to_string())?;
+	let variant = variants
+		.iter()
+		.find(|variant| variant.index == call_index)
+		.ok_or_else(|| format!("{} has no call with index {}", pallet.name, call_index))?;
+	let args = decode_fields(&metadata.types, &variant.fields, input, 0)?;
+	if !input.is_empty() {
+		return Err(format!("{} trailing bytes after the call", input.len()))
+	}
+	Ok(json!({ "pallet": pallet.name, "call": variant.name, "args": args }))
+}
+
+fn resolve(types: &PortableRegistry, id: u32) -> Result<&scale_info::Type<PortableForm>, String> {
+	types.resolve(id).ok_or_else(|| format!("type {} is not in the metadata", id))
+}
+
+/// Named fields become an object, unnamed ones an array, and a single unnamed field (a newtype)
+/// is unwrapped.
+fn decode_fields(
+	types: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, String> {
+	if fields.iter().all(|field| field.name.is_some()) {
+		let mut object = Map::new();
+		for field in fields {
+			let name = field.name.clone().unwrap_or_default();
+			object.insert(name, decode_type(types, field.ty.id, input, depth)?);
+		}
+		return Ok(Value::Object(object))
+	}
+	let mut values = fields
+		.iter()
+		.map(|field| decode_type(types, field.ty.id, input, depth))
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok(if values.len() == 1 { values.remove(0) } else { Value::Array(values) })
+}
+
+fn decode_type(
+	types: &PortableRegistry,
+	id: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, String> {
+	if depth >= MAX_DEPTH {
+		return Err(format!("types are nested more than {} levels deep", MAX_DEPTH))
+	}
+	let depth = depth + 1;
+	let ty = resolve(types, id)?;
+	if ty.path.segments.last().map(|name| name.as_str()) == Some("AccountId32") {
+		let account = <[u8; 32]>::decode(input).map_err(|err| err.to_string())?;
+		return Ok(Value::String(AccountId32::from(account).to_ss58check()))
+	}
+	match &ty.type_def {
+		TypeDef::Composite(composite) => decode_fields(types, &composite.fields, input, depth),
+		TypeDef::Variant(enumeration) => {
+			let index = u8::decode(input).map_err(|err| err.to_string())?;
+			let variant = enumeration
+				.variants
+				.iter()
+				.find(|variant| variant.index == index)
+				.ok_or_else(|| format!("type {} has no variant with index {}", id, index))?;
+			if variant.fields.is_empty() {
+				return Ok(Value::String(variant.name.clone()))
+			}
+			let mut object = Map::new();
+			object.insert(
+				variant.name.clone(),
+				decode_fields(types, &variant.fields, input, depth)?,
+			);
+			Ok(Value::Object(object))
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input).map_err(|err| err.to_string())?.0;
+			decode_items(types, sequence.type_param.id, len, input, depth)
+		},
+		TypeDef::Array(array) => decode_items(types, array.type_param.id, array.len, input, depth),
+		TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Ok(Value::Null),
+		TypeDef::Tuple(tuple) => tuple
+			.fields
+			.iter()
+			.map(|field| decode_type(types, field.id, input, depth))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Value::Array),
+		TypeDef::Primitive(primitive) => decode_primitive(primitive, input),
+		TypeDef::Compact(compact) => decode_compact(types, compact.type_param.id, input, depth),
+		TypeDef::BitSequence(_) => Err("bit sequences are not supported".into()),
+	}
+}
+
+/// Byte sequences and arrays are shown as hex, everything else as a JSON array.
+fn decode_items(
+	types: &PortableRegistry,
+	item: u32,
+	len: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, String> {
+	if matches!(resolve(types, item)?.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)) {
+		let len = len as usize;
+		if input.len() < len {
+			return Err("not enough bytes for the byte sequence".into())
+		}
+		let remaining: &[u8] = *input;
+		let (bytes, rest) = remaining.split_at(len);
+		*input = rest;
+		return Ok(Value::String(format!("0x{}", HexDisplay::from(&bytes[..]))))
+	}
+	(0..len)
+		.map(|_| decode_type(types, item, input, depth))
+		.collect::<Result<Vec<_>, _>>()
+		.map(Value::Array)
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, String> {
+	fn decode<T: Decode>(input: &mut &[u8]) -> Result<T, String> {
+		T::decode(input).map_err(|err| err.to_string())
+	}
+	Ok(match primitive {
+		TypeDefPrimitive::Bool => json!(decode::<bool>(input)?),
+		TypeDefPrimitive::Char => {
+			let code = decode::<u32>(input)?;
+			json!(char::from_u32(code).ok_or_else(|| format!("{} is not a char", code))?)
+		},
+		TypeDefPrimitive::Str => json!(decode::<String>(input)?),
+		TypeDefPrimitive::U8 => json!(decode::<u8>(input)?),
+		TypeDefPrimitive::U16 => json!(decode::<u16>(input)?),
+		TypeDefPrimitive::U32 => json!(decode::<u32>(input)?),
+		TypeDefPrimitive::U64 => json!(decode::<u64>(input)?),
+		TypeDefPrimitive::U128 => json!(decode::<u128>(input)?.to_string()),
+		TypeDefPrimitive::I8 => json!(decode::<i8>(input)?),
+		TypeDefPrimitive::I16 => json!(decode::<i16>(input)?),
+		TypeDefPrimitive::I32 => json!(decode::<i32>(input)?),
+		TypeDefPrimitive::I64 => json!(decode::<i64>(input)?),
+		TypeDefPrimitive::I128 => json!(decode::<i128>(input)?.to_string()),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			let bytes = decode::<[u8; 32]>(input)?;
+			json!(format!("0x{}", HexDisplay::from(&bytes[..])))
+		},
+	})
+}
+
+/// Compact encoding only exists for unsigned integers and for single field structs wrapping one.
+fn decode_compact(
+	types: &PortableRegistry,
+	id: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, String> {
+	if depth >= MAX_DEPTH {
+		return Err(format!("types are nested more than {} levels deep", MAX_DEPTH))
+	}
+	fn decode<T>(input: &mut &[u8]) -> Result<T, String>
+	where
+		Compact<T>: Decode,
+	{
+		Compact::<T>::decode(input).map(|compact| compact.0).map_err(|err| err.to_string())
+	}
+	match &resolve(types, id)?.type_def {
+		TypeDef::Primitive(TypeDefPrimitive::U8) => Ok(json!(decode::<u8>(input)?)),
+		TypeDef::Primitive(TypeDefPrimitive::U16) => Ok(json!(decode::<u16>(input)?)),
+		TypeDef::Primitive(TypeDefPrimitive::U32) => Ok(json!(decode::<u32>(input)?)),
+		TypeDef::Primitive(TypeDefPrimitive::U64) => Ok(json!(decode::<u64>(input)?)),
+		TypeDef::Primitive(TypeDefPrimitive::U128) =>
+			Ok(json!(decode::<u128>(input)?.to_string())),
+		TypeDef::Composite(composite) if composite.fields.len() == 1 =>
+			decode_compact(types, composite.fields[0].ty.id, input, depth + 1),
+		TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Ok(Value::Null),
+		_ => Err(format!("type {} can not be compact encoded", id)),
+	}
+}
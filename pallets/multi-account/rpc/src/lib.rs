@@ -1,14 +1,27 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use multi_runtime_api::MultiAccountApi as AccountApi;
+mod decode;
+
+pub use multi_runtime_api::{
+	AccountEvent, AccountPolicy, AccountView, BlockedReason, DryRunInfo,
+	MultiAccountApi as AccountApi, MultiAccountApiError, ProposalView, RecoveryView, ScheduleView,
+	SignatoryView, SubAccountView,
+};
+use futures::{FutureExt, StreamExt};
 use jsonrpsee::{
-	core::{Error as JsonRpseeError, RpcResult},
+	core::RpcResult,
 	proc_macros::rpc,
-	types::error::{CallError, ErrorObject},
+	types::{error::SubscriptionClosed, SubscriptionResult},
+	SubscriptionSink,
 };
-use sp_api::ProvideRuntimeApi;
+use rpc_errors::{block_hash, Error};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
+use frame_metadata::v14::RuntimeMetadataV14;
+use sp_api::{ApiError, ApiExt, Metadata, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::Block as BlockT;
+use sp_core::Bytes;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 use std::sync::Arc;
 use codec::Codec;
 
@@ -19,24 +32,140 @@ pub struct Custom {
 }
 type CallHash = [u8; 32];
 
+/// A pending proposal as shown to the signers that still have to approve it.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalDetails<AccountId> {
+	/// Signatories that approved the call so far.
+	pub approvals: Vec<AccountId>,
+	/// Number of approvals needed before the call is dispatched.
+	pub threshold: u32,
+	/// The decoded call, or `None` if its preimage is not kept on chain.
+	pub call: Option<serde_json::Value>,
+	/// The Connect names of `approvals`, in the same order. `None` for signatories that are not
+	/// registered users.
+	pub approval_names: Vec<Option<String>>,
+}
+
+/// A multi-account as shown to users: the runtime's `AccountView` with the Connect names of its
+/// signatories.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo<AccountId, Balance, BlockNumber> {
+	#[serde(flatten)]
+	pub view: AccountView<AccountId, Balance, BlockNumber>,
+	/// The Connect names of `signatories`, in the same order. `None` for signatories that are not
+	/// registered users.
+	pub signatory_names: Vec<Option<String>>,
+}
+
+/// A signatory of a multi-account as shown to users.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Signatory<AccountId> {
+	/// The signatory.
+	pub account: AccountId,
+	/// The name the signatory registered with Connect, if it is a registered user.
+	pub name: Option<String>,
+	/// Whether the signatory is a verified Connect user and can propose and approve calls.
+	pub verified: bool,
+}
+
+impl<AccountId> From<SignatoryView<AccountId>> for Signatory<AccountId> {
+	fn from(view: SignatoryView<AccountId>) -> Self {
+		Signatory {
+			account: view.account,
+			name: view.name.map(|name| String::from_utf8_lossy(&name).into_owned()),
+			verified: view.verified,
+		}
+	}
+}
+
 #[rpc(client, server)]
-pub trait MultiAccountApi<BlockHash, AccountId> {
+pub trait MultiAccountApi<BlockHash, BlockNumber, AccountId, Balance> {
 
     /// get the number of accounts that have approved a particular call hash
     #[method(name = "multi_NumberOfAccountsHasApprovedCall")]
-    fn get_approvals_for_call(&self, id: AccountId, call_hash: CallHash, at: Option<BlockHash> ) -> RpcResult<Option<u32>>;
+    fn get_approvals_for_call(&self, id: AccountId, call_hash: CallHash, at: Option<BlockHash> ) -> RpcResult<u32>;
     
     /// get the signatories for an account
     #[method(name = "multi_AccountSigners")]    
-    fn get_signatories_for_account(&self, id: AccountId, at: Option<BlockHash> ) -> RpcResult<Option<Vec<AccountId>>>;
+    fn get_signatories_for_account(&self, id: AccountId, at: Option<BlockHash> ) -> RpcResult<Vec<AccountId>>;
     
     /// get the threshold required for a call to pass
     #[method(name = "multi_AccountThreshold")]
-    fn get_threshold_for_account(&self, id: AccountId, at: Option<BlockHash> ) -> RpcResult<Option<u32>>;
+    fn get_threshold_for_account(&self, id: AccountId, at: Option<BlockHash> ) -> RpcResult<u32>;
     
     /// get the accounts that has approved a particular call
     #[method(name = "multi_SignersWhoApprovedCall")]
-    fn get_approval_accounts_for_call(&self, id: AccountId, call_hash:CallHash, at: Option<BlockHash> ) -> RpcResult<Option<Vec<AccountId>>>;
+    fn get_approval_accounts_for_call(&self, id: AccountId, call_hash:CallHash, at: Option<BlockHash> ) -> RpcResult<Vec<AccountId>>;
+
+    /// get the signatories with their Connect names, threshold, balance, nonce, pending proposal
+    /// count and policy of an account in one call. Needs version 2 of the runtime API.
+    #[method(name = "multi_accountInfo")]
+    fn account_info(&self, id: AccountId, at: Option<BlockHash>) -> RpcResult<AccountInfo<AccountId, Balance, BlockNumber>>;
+
+    /// get the recurring transfers of an account. Needs version 3 of the runtime API.
+    #[method(name = "multi_schedules")]
+    fn schedules(&self, id: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<ScheduleView<AccountId, Balance, BlockNumber>>>;
+
+    /// get the calls of an account that wait for approvals, with the title, description hash and
+    /// tags their proposers gave them. Needs version 4 of the runtime API.
+    #[method(name = "multi_pendingProposals")]
+    fn pending_proposals(&self, id: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<ProposalView<AccountId>>>;
+
+    /// get why a pending call with enough approvals is not dispatched yet, `null` if only
+    /// approvals are missing. Needs version 5 of the runtime API.
+    #[method(name = "multi_blockedReason")]
+    fn blocked_reason(&self, id: AccountId, call_hash: CallHash, at: Option<BlockHash>) -> RpcResult<Option<BlockedReason<BlockNumber>>>;
+
+    /// get the sub-accounts of an account with their addresses and balances. Needs version 6 of
+    /// the runtime API.
+    #[method(name = "multi_subAccounts")]
+    fn sub_accounts(&self, id: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<SubAccountView<AccountId, Balance>>>;
+
+    /// get the signatories of an account with their Connect names and whether they are verified
+    /// users. Needs version 7 of the runtime API.
+    #[method(name = "multi_signatories")]
+    fn signatories(&self, id: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<Signatory<AccountId>>>;
+
+    /// get the pending recoveries of lost signatory keys of an account, with their votes and the
+    /// block they can be finalized in. Needs version 8 of the runtime API.
+    #[method(name = "multi_recoveries")]
+    fn recoveries(&self, id: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<RecoveryView<AccountId, BlockNumber>>>;
+
+    /// get the approvals a pending call needs now, which is lower than the threshold once the
+    /// call is stuck long enough under the threshold decay of its account. Needs version 9 of
+    /// the runtime API.
+    #[method(name = "multi_effectiveThreshold")]
+    fn effective_threshold(&self, id: AccountId, call_hash: CallHash, at: Option<BlockHash>) -> RpcResult<u32>;
+
+    /// simulate dispatching a SCALE encoded call as the multi-account without persisting anything
+    #[method(name = "multi_dryRun")]
+    fn dry_run(&self, id: AccountId, call: Bytes, at: Option<BlockHash>) -> RpcResult<DryRunInfo<Balance>>;
+
+    /// derive the multi-account address for a set of signatories (in any order) and a threshold
+    #[method(name = "multi_deriveAccountId")]
+    fn derive_account_id(&self, signatories: Vec<AccountId>, threshold: u16, at: Option<BlockHash>) -> RpcResult<AccountId>;
+
+    /// decode a SCALE encoded call into its pallet, call name and named arguments
+    #[method(name = "multi_decodeCall")]
+    fn decode_call(&self, call: Bytes, at: Option<BlockHash>) -> RpcResult<serde_json::Value>;
+
+    /// get the approvals with their Connect names, threshold and decoded call of a pending
+    /// proposal
+    #[method(name = "multi_proposalDetails")]
+    fn proposal_details(&self, id: AccountId, call_hash: CallHash, at: Option<BlockHash>) -> RpcResult<ProposalDetails<AccountId>>;
+
+    /// stream the proposals, approvals and executions of a multi-account as blocks are imported,
+    /// as well as the calls that expired or were removed. Pass `finalized = true` to only follow
+    /// finalized blocks.
+    #[subscription(
+        name = "multi_subscribeProposals" => "multi_proposal",
+        unsubscribe = "multi_unsubscribeProposals",
+        item = AccountEvent<AccountId>
+    )]
+    fn subscribe_proposals(&self, id: AccountId, finalized: Option<bool>);
 }
 
 /// A struct that implements the `TemplateApi`.
@@ -44,64 +173,312 @@ pub struct MultiAccountPallet<C, Block> {
 	// If you have more generics, no need to TemplatePallet<C, M, N, P, ...>
 	// just use a tuple like TemplatePallet<C, (M, N, P, ...)>
 	client: Arc<C>,
+	// subscriptions are driven by tasks spawned on this executor
+	executor: SubscriptionTaskExecutor,
 	_marker: std::marker::PhantomData<Block>,
 }
 
 impl<C, Block> MultiAccountPallet<C, Block> {
 	/// Create new `TemplatePallet` instance with the given reference to the client.
-	pub fn new(client: Arc<C>) -> Self {
-		Self { client, _marker: Default::default() }
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> MultiAccountPallet<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: Metadata<Block>,
+{
+	/// The metadata of the runtime at `at`, used to decode calls made for that runtime.
+	fn metadata(&self, at: <Block as BlockT>::Hash) -> RpcResult<RuntimeMetadataV14> {
+		let metadata = self.client.runtime_api().metadata(at).map_err(Error::runtime_api)?;
+		Ok(decode::metadata_from_bytes(metadata.as_slice()).map_err(Error::Metadata)?)
+	}
+}
+
+impl<C, Block, AccountId, Balance> MultiAccountPallet<C, Block>
+where
+	Block: BlockT,
+	AccountId: Codec,
+	Balance: Codec,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: AccountApi<Block, AccountId, Balance>,
+{
+	/// The version of the runtime API implemented by the runtime at `at`.
+	fn api_version(&self, at: <Block as BlockT>::Hash) -> RpcResult<u32> {
+		Ok(self
+			.client
+			.runtime_api()
+			.api_version::<dyn AccountApi<Block, AccountId, Balance>>(at)
+			.map_err(Error::runtime_api)?
+			.unwrap_or_default())
+	}
+
+	/// Fail with an `ApiVersionMismatch` if the runtime at `at` implements a version of the
+	/// runtime API older than `required`.
+	fn ensure_api_version(&self, at: <Block as BlockT>::Hash, required: u32) -> RpcResult<()> {
+		let found = self.api_version(at)?;
+		if found < required {
+			return Err(Error::ApiVersionMismatch { api: "MultiAccountApi", required, found }.into())
+		}
+		Ok(())
+	}
+
+	/// The Connect names of `accounts`, signatories of `id`, in the same order. Runtimes from
+	/// before version 7 of the runtime API can't tell, so all names are `None` there.
+	fn signatory_names(
+		&self,
+		id: AccountId,
+		accounts: &[AccountId],
+		at: <Block as BlockT>::Hash,
+	) -> RpcResult<Vec<Option<String>>>
+	where
+		AccountId: PartialEq,
+	{
+		let signatories = if self.api_version(at)? < 7 {
+			Vec::new()
+		} else {
+			into_rpc_result(self.client.runtime_api().signatories(at, id))?
+		};
+		Ok(accounts
+			.iter()
+			.map(|account| {
+				signatories
+					.iter()
+					.find(|signatory| signatory.account == *account)
+					.and_then(|signatory| signatory.name.as_ref())
+					.map(|name| String::from_utf8_lossy(name).into_owned())
+			})
+			.collect())
 	}
 }
 
-impl<C, Block, AccountId: Codec> MultiAccountApiServer<<Block as BlockT>::Hash, AccountId> for MultiAccountPallet<C, Block>
+impl<C, Block, AccountId, Balance: Codec> MultiAccountApiServer<<Block as BlockT>::Hash, NumberFor<Block>, AccountId, Balance> for MultiAccountPallet<C, Block>
 where
 	Block: BlockT,
-	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
-	C::Api: AccountApi<Block, AccountId>,
+	AccountId: Codec + Clone + PartialEq + serde::Serialize + Send + Sync + 'static,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C::Api: AccountApi<Block, AccountId, Balance> + Metadata<Block>,
 {
-	fn get_approvals_for_call(&self, id: AccountId, call: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<u32>> {
+	fn get_approvals_for_call(&self, id: AccountId, call: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<u32> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		if self.api_version(block_hash)? < 2 {
+			#[allow(deprecated)]
+			let approvals = api.get_approvals_for_call_before_version_2(block_hash, id, call);
+			return from_option(approvals, Error::CallNotFound)
+		}
+		into_rpc_result(api.get_approvals_for_call(block_hash, id, call))
+	}
+
+    fn get_signatories_for_account(&self, id: AccountId,at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<AccountId>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		if self.api_version(block_hash)? < 2 {
+			#[allow(deprecated)]
+			let signatories = api.get_signatories_for_account_before_version_2(block_hash, id);
+			return from_option(signatories, Error::UnknownAccount)
+		}
+		into_rpc_result(api.get_signatories_for_account(block_hash, id))
+	}
+
+    fn get_threshold_for_account(&self, id: AccountId,at: Option<<Block as BlockT>::Hash>) -> RpcResult<u32> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		if self.api_version(block_hash)? < 2 {
+			#[allow(deprecated)]
+			let threshold = api.get_threshold_for_account_before_version_2(block_hash, id);
+			return from_option(threshold, Error::UnknownAccount)
+		}
+		into_rpc_result(api.get_threshold_for_account(block_hash, id))
+	}
+
+    fn get_approval_accounts_for_call(&self, id: AccountId, call: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<AccountId>> {
 		let api = self.client.runtime_api();
-		let block_hash = at.unwrap_or_else(||
-			// If the block hash is not supplied assume the best block.
-			self.client.info().best_hash);
-		api.get_approvals_for_call(block_hash, id, call).map_err(runtime_error_into_rpc_err)
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		if self.api_version(block_hash)? < 2 {
+			#[allow(deprecated)]
+			let approvals =
+				api.get_approval_accounts_for_call_before_version_2(block_hash, id, call);
+			return from_option(approvals, Error::CallNotFound)
+		}
+		into_rpc_result(api.get_approval_accounts_for_call(block_hash, id, call))
 	}
 
-    fn get_signatories_for_account(&self, id: AccountId,at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<Vec<AccountId>>> {
+	fn account_info(&self, id: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<AccountInfo<AccountId, Balance, NumberFor<Block>>> {
 		let api = self.client.runtime_api();
-		let block_hash = at.unwrap_or_else(||
-			// If the block hash is not supplied assume the best block.
-			self.client.info().best_hash);
-		api.get_signatories_for_account(block_hash, id).map_err(runtime_error_into_rpc_err)
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		// runtimes from before version 2 only have the separate getters
+		self.ensure_api_version(block_hash, 2)?;
+		let view = api
+			.account_info(block_hash, id.clone())
+			.map_err(Error::runtime_api)?
+			.ok_or(Error::UnknownAccount)?;
+		let signatory_names = self.signatory_names(id, &view.signatories, block_hash)?;
+		Ok(AccountInfo { view, signatory_names })
 	}
 
-    fn get_threshold_for_account(&self, id: AccountId,at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<u32>> {
+	fn schedules(&self, id: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<ScheduleView<AccountId, Balance, NumberFor<Block>>>> {
 		let api = self.client.runtime_api();
-		let block_hash = at.unwrap_or_else(||
-			// If the block hash is not supplied assume the best block.
-			self.client.info().best_hash);
-		api.get_threshold_for_account(block_hash, id).map_err(runtime_error_into_rpc_err)
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 3)?;
+		Ok(api.schedules(block_hash, id).map_err(Error::runtime_api)?)
 	}
 
-    fn get_approval_accounts_for_call(&self, id: AccountId, call: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<Vec<AccountId>>> {
+	fn pending_proposals(&self, id: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<ProposalView<AccountId>>> {
 		let api = self.client.runtime_api();
-		let block_hash = at.unwrap_or_else(||
-			// If the block hash is not supplied assume the best block.
-			self.client.info().best_hash);
-		api.get_approval_accounts_for_call(block_hash, id, call).map_err(runtime_error_into_rpc_err)
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 4)?;
+		Ok(api.pending_proposals(block_hash, id).map_err(Error::runtime_api)?)
+	}
+
+	fn blocked_reason(&self, id: AccountId, call_hash: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<BlockedReason<NumberFor<Block>>>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 5)?;
+		into_rpc_result(api.blocked_reason(block_hash, id, call_hash))
+	}
+
+	fn sub_accounts(&self, id: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<SubAccountView<AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 6)?;
+		Ok(api.sub_accounts(block_hash, id).map_err(Error::runtime_api)?)
+	}
+
+	fn signatories(&self, id: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<Signatory<AccountId>>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 7)?;
+		let signatories = into_rpc_result(api.signatories(block_hash, id))?;
+		Ok(signatories.into_iter().map(Signatory::from).collect())
+	}
+
+	fn recoveries(&self, id: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<RecoveryView<AccountId, NumberFor<Block>>>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 8)?;
+		Ok(api.recoveries(block_hash, id).map_err(Error::runtime_api)?)
+	}
+
+	fn effective_threshold(&self, id: AccountId, call_hash: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<u32> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 9)?;
+		into_rpc_result(api.effective_threshold(block_hash, id, call_hash))
+	}
+
+    fn dry_run(&self, id: AccountId, call: Bytes, at: Option<<Block as BlockT>::Hash>) -> RpcResult<DryRunInfo<Balance>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 2)?;
+		into_rpc_result(api.dry_run(block_hash, id, call.to_vec()))
+	}
+
+    fn derive_account_id(&self, signatories: Vec<AccountId>, threshold: u16, at: Option<<Block as BlockT>::Hash>) -> RpcResult<AccountId> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		self.ensure_api_version(block_hash, 2)?;
+		into_rpc_result(api.derive_account_id(block_hash, signatories, threshold))
+	}
+
+	fn decode_call(&self, call: Bytes, at: Option<<Block as BlockT>::Hash>) -> RpcResult<serde_json::Value> {
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		let metadata = self.metadata(block_hash)?;
+		Ok(decode::decode_call(&metadata, &call).map_err(|reason| Error::InvalidCall(Some(reason)))?)
+	}
+
+	fn proposal_details(&self, id: AccountId, call_hash: CallHash, at: Option<<Block as BlockT>::Hash>) -> RpcResult<ProposalDetails<AccountId>> {
+		let api = self.client.runtime_api();
+		let block_hash = block_hash::<Block, _>(&*self.client, at)?;
+		let approvals =
+			self.get_approval_accounts_for_call(id.clone(), call_hash, Some(block_hash))?;
+		let threshold = self.get_threshold_for_account(id.clone(), Some(block_hash))?;
+		self.ensure_api_version(block_hash, 2)?;
+		let data = into_rpc_result(api.get_call_data(block_hash, id.clone(), call_hash))?;
+		let call = match data {
+			Some(data) => Some(
+				decode::decode_call(&self.metadata(block_hash)?, &data)
+					.map_err(|reason| Error::InvalidCall(Some(reason)))?,
+			),
+			None => None,
+		};
+		let approval_names = self.signatory_names(id, &approvals, block_hash)?;
+		Ok(ProposalDetails { approvals, threshold, call, approval_names })
+	}
+
+	fn subscribe_proposals(&self, mut sink: SubscriptionSink, id: AccountId, finalized: Option<bool>) -> SubscriptionResult {
+		let blocks = if finalized.unwrap_or(false) {
+			self.client.finality_notification_stream().map(|block| block.hash).boxed()
+		} else {
+			self.client
+				.import_notification_stream()
+				.filter(|block| futures::future::ready(block.is_new_best))
+				.map(|block| block.hash)
+				.boxed()
+		};
+		let client = self.client.clone();
+		// the events of a block are read from its state, so every block becomes zero or more
+		// items on the subscription. If they can't be read, or the runtime of the block is older
+		// than version 2 of the runtime API, the subscription is closed, rather than skipping the
+		// block without the subscriber noticing.
+		let events = blocks
+			.flat_map(move |hash| {
+				let api = client.runtime_api();
+				let version = api.api_version::<dyn AccountApi<Block, AccountId, Balance>>(hash);
+				let events: Vec<Result<_, String>> = match version {
+					Ok(Some(version)) if version >= 2 => match api.account_events(hash, id.clone()) {
+						Ok(events) => events.into_iter().map(Ok).collect(),
+						Err(err) => vec![Err(format!(
+							"Failed to read the events of block {:?}: {:?}",
+							hash, err
+						))],
+					},
+					Ok(found) => vec![Err(format!(
+						"The runtime of block {:?} implements version {} of MultiAccountApi, \
+						 version 2 is required",
+						hash,
+						found.unwrap_or_default()
+					))],
+					Err(err) => vec![Err(format!(
+						"Failed to read the runtime API version of block {:?}: {:?}",
+						hash, err
+					))],
+				};
+				futures::stream::iter(events)
+			})
+			.boxed();
+
+		let fut = async move {
+			if let SubscriptionClosed::Failed(err) = sink.pipe_from_try_stream(events).await {
+				sink.close(err);
+			}
+		};
+		self.executor.spawn("multi-account-proposals", Some("rpc"), fut.boxed());
+		Ok(())
 	}
 
 }
 
-const RUNTIME_ERROR: i32 = 1;
+/// Converts the result of a version 1 runtime API call, which reports every failure as `None`,
+/// into an RPC result failing with `none`.
+fn from_option<T>(result: Result<Option<T>, ApiError>, none: Error) -> RpcResult<T> {
+	result.map_err(Error::runtime_api)?.ok_or_else(|| none.into())
+}
 
-/// Converts a runtime trap into an RPC error.
-fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
-	CallError::Custom(ErrorObject::owned(
-		RUNTIME_ERROR,
-		"Runtime error",
-		Some(format!("{:?}", err)),
-	))
-	.into()
+/// Converts the result of a runtime API call into an RPC result, keeping the runtime call failing
+/// apart from the API reporting an error.
+fn into_rpc_result<T>(result: Result<Result<T, MultiAccountApiError>, ApiError>) -> RpcResult<T> {
+	let result = result.map_err(Error::runtime_api)?;
+	result.map_err(|err| {
+		match err {
+			MultiAccountApiError::UnknownAccount => Error::UnknownAccount,
+			MultiAccountApiError::CallNotFound => Error::CallNotFound,
+			MultiAccountApiError::InvalidCall => Error::InvalidCall(None),
+			MultiAccountApiError::InvalidSignatories => Error::InvalidSignatories,
+		}
+		.into()
+	})
 }
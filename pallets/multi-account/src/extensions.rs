@@ -0,0 +1,316 @@
+use crate::{BalanceOf, Call, Config, Pallet, SignatoryValidator};
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::{DispatchInfo, DispatchResult, Dispatchable, PostDispatchInfo},
+	traits::{Get, IsSubType},
+};
+use pallet_transaction_payment::{
+	ChargeTransactionPayment, Config as PaymentConfig, OnChargeTransaction,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, SaturatedConversion, SignedExtension, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	FixedPointOperand,
+};
+use core::{fmt, marker::PhantomData};
+
+/// The balance type transaction fees are charged in.
+type FeeOf<T> = <<T as PaymentConfig>::OnChargeTransaction as OnChargeTransaction<T>>::Balance;
+
+/// Charges transaction fees like `ChargeTransactionPayment`, which it replaces in the runtime,
+/// except that the multi-account pays the fee of `account_create_call` and
+/// `approve_or_dispatch_call` for its signatories, and of `approve_as_delegate` for their current
+/// delegates.
+///
+/// A transaction is sponsored when its signer passes the `SignatoryValidator`, it carries no tip
+/// and the account's remaining fee budget (see `set_fee_budget`) covers its fee. The fee is then
+/// withdrawn from the multi-account instead of the signer, who doesn't need to hold any funds,
+/// and taken out of the budget. After dispatch the part of the fee that wasn't used goes back to
+/// both. Only calls that succeed are paid for: the signer of a call that failed is charged the
+/// actual fee after all, and the budget is restored. Every other transaction pays its own fee.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct SponsorApprovalFees<T: Config + PaymentConfig + Send + Sync>(
+	#[codec(compact)] FeeOf<T>,
+);
+
+impl<T: Config + PaymentConfig + Send + Sync> SponsorApprovalFees<T> {
+	/// Include a tip. Transactions with a tip pay their own fee.
+	pub fn from(tip: FeeOf<T>) -> Self {
+		Self(tip)
+	}
+
+	/// The multi-account that pays the fee of `call` for `who`, and that fee.
+	fn sponsor(
+		&self,
+		who: &T::AccountId,
+		call: &<T as frame_system::Config>::RuntimeCall,
+		info: &DispatchInfoOf<<T as frame_system::Config>::RuntimeCall>,
+		len: usize,
+	) -> Option<(T::AccountId, FeeOf<T>)>
+	where
+		<T as frame_system::Config>::RuntimeCall:
+			IsSubType<Call<T>> + Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+		FeeOf<T>: FixedPointOperand,
+	{
+		if !self.0.is_zero() || !T::SignatoryValidator::is_valid(who) {
+			return None
+		}
+		// signatories are checked before dispatch, so an approval that completes a call which
+		// changes the signatories is still paid for
+		let id = match call.is_sub_type() {
+			Some(Call::account_create_call { id, .. }) |
+			Some(Call::approve_or_dispatch_call { id, .. })
+				if Pallet::<T>::is_signatory(id, who) =>
+				id,
+			Some(Call::approve_as_delegate { id, signer, .. })
+				if Pallet::<T>::is_delegate(id, signer, who) =>
+				id,
+			_ => return None,
+		};
+		let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(
+			len as u32,
+			info,
+			Zero::zero(),
+		);
+		let budget = Pallet::<T>::fee_budget(id);
+		if budget.is_zero() || to_balance::<T>(fee) > budget {
+			return None
+		}
+		Some((id.clone(), fee))
+	}
+}
+
+impl<T: Config + PaymentConfig + Send + Sync> fmt::Debug
+	for SponsorApprovalFees<T>
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "SponsorApprovalFees<{:?}>", self.0)
+	}
+}
+
+impl<T> SignedExtension for SponsorApprovalFees<T>
+where
+	T: Config + PaymentConfig + Send + Sync,
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>
+		+ Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	FeeOf<T>: Send + Sync + FixedPointOperand,
+	ChargeTransactionPayment<T>: SignedExtension<
+		AccountId = T::AccountId,
+		Call = <T as frame_system::Config>::RuntimeCall,
+	>,
+{
+	const IDENTIFIER: &'static str = "SponsorApprovalFees";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	/// The signer, the multi-account that pays for it with the fee it paid, and what
+	/// `ChargeTransactionPayment` needs to correct the fee.
+	type Pre = (
+		T::AccountId,
+		Option<(T::AccountId, FeeOf<T>)>,
+		<ChargeTransactionPayment<T> as SignedExtension>::Pre,
+	);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let payer = self.sponsor(who, call, info, len).map_or_else(|| who.clone(), |(id, _)| id);
+		ChargeTransactionPayment::<T>::from(self.0).validate(&payer, call, info, len)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let sponsor = self.sponsor(who, call, info, len);
+		let payer = sponsor.as_ref().map_or(who, |(id, _)| id);
+		let pre = ChargeTransactionPayment::<T>::from(self.0).pre_dispatch(payer, call, info, len)?;
+		if let Some((id, fee)) = &sponsor {
+			Pallet::<T>::take_fee_budget(id, to_balance::<T>(*fee));
+		}
+		Ok((who.clone(), sponsor, pre))
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		if let Some((who, sponsor, pre)) = pre {
+			ChargeTransactionPayment::<T>::post_dispatch(Some(pre), info, post_info, len, result)?;
+			if let Some((id, estimate)) = sponsor {
+				let fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+					len as u32,
+					info,
+					post_info,
+					Zero::zero(),
+				);
+				Pallet::<T>::settle_fee(
+					&id,
+					&who,
+					to_balance::<T>(estimate),
+					to_balance::<T>(fee),
+					result.is_ok(),
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Converts a transaction fee into the currency of the pallet.
+fn to_balance<T: Config + PaymentConfig>(fee: FeeOf<T>) -> BalanceOf<T> {
+	fee.saturated_into::<u128>().saturated_into()
+}
+
+/// The signer is not a signatory of the multi-account.
+pub const NOT_A_SIGNATORY: u8 = 1;
+/// The signer has already approved the call.
+pub const ALREADY_APPROVED: u8 = 2;
+/// A call with the same hash is already pending for the multi-account.
+pub const ALREADY_PROPOSED: u8 = 3;
+/// The call was proposed under a different transaction version and has to be proposed again.
+pub const STALE_PROPOSAL: u8 = 4;
+/// A guardian froze the multi-account.
+pub const ACCOUNT_FROZEN: u8 = 5;
+/// The signer is not the current delegate of the signatory it approves for.
+pub const NOT_A_DELEGATE: u8 = 6;
+/// The signer, or the signatory a delegate approves for, doesn't pass the `SignatoryValidator`.
+pub const NOT_VERIFIED: u8 = 7;
+
+/// Rejects `account_create_call`, `approve_or_dispatch_call` and `approve_as_delegate`
+/// transactions that would fail at dispatch before they enter the transaction pool.
+///
+/// Transactions from signers that are not signatories or don't pass the `SignatoryValidator`,
+/// second approvals of the same signer and approvals of calls that were already dispatched are
+/// invalid. An approval by a delegate is checked as the approval of the signatory it is made for,
+/// and is invalid once the delegation expired. A proposal provides a tag for its call, so the same
+/// call can only be proposed once in the pool, and approvals of a call that is not on chain yet
+/// require that tag. The approval that reaches the threshold gets its priority
+/// raised by [`Config::ApprovalPriorityBoost`] so that the call is dispatched sooner.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckMultiAccountApproval<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckMultiAccountApproval<T> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckMultiAccountApproval<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for CheckMultiAccountApproval<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckMultiAccountApproval")
+	}
+}
+
+impl<T> SignedExtension for CheckMultiAccountApproval<T>
+where
+	T: Config + Send + Sync,
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "CheckMultiAccountApproval";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		let (id, inner, proposal, who) = match call.is_sub_type() {
+			Some(Call::account_create_call { id, call }) => (id, call, true, who),
+			Some(Call::approve_or_dispatch_call { id, call }) => (id, call, false, who),
+			Some(Call::approve_as_delegate { id, signer, call }) => {
+				if !Pallet::<T>::is_delegate(id, signer, who) {
+					return Err(InvalidTransaction::Custom(NOT_A_DELEGATE).into())
+				}
+				// from here on it is checked as the approval of `signer`
+				(id, call, false, signer)
+			},
+			_ => return Ok(ValidTransaction::default()),
+		};
+		if !Pallet::<T>::is_signatory(id, who) {
+			return Err(InvalidTransaction::Custom(NOT_A_SIGNATORY).into())
+		}
+		if !T::SignatoryValidator::is_valid(who) {
+			return Err(InvalidTransaction::Custom(NOT_VERIFIED).into())
+		}
+		if Pallet::<T>::is_frozen(id) {
+			return Err(InvalidTransaction::Custom(ACCOUNT_FROZEN).into())
+		}
+		let hash = inner.using_encoded(sp_io::hashing::blake2_256);
+		let stale = Pallet::<T>::is_stale(id, &hash);
+		if stale && !proposal {
+			return Err(InvalidTransaction::Custom(STALE_PROPOSAL).into())
+		}
+		// a stale proposal is replaced when the call is proposed again
+		let approvals = if stale { Default::default() } else { Pallet::<T>::get_call(id, hash) };
+		let threshold = Pallet::<T>::effective_threshold(id, &hash) as usize;
+		// the call has been dispatched already. the receipt tells even if the threshold was
+		// raised since, e.g. by turning off its decay
+		if Pallet::<T>::succeeded(id, &hash) || approvals.len() >= threshold {
+			return Err(InvalidTransaction::Stale.into())
+		}
+		if approvals.contains(who) {
+			return Err(InvalidTransaction::Custom(ALREADY_APPROVED).into())
+		}
+
+		let proposal_tag = (b"multi-account/proposal", id, hash).encode();
+		let mut valid = ValidTransaction::with_tag_prefix("MultiAccount")
+			.and_provides((b"multi-account/approval", id, hash, who).encode());
+		if proposal {
+			if !approvals.is_empty() {
+				return Err(InvalidTransaction::Custom(ALREADY_PROPOSED).into())
+			}
+			valid = valid.and_provides(proposal_tag);
+		} else if approvals.is_empty() {
+			// wait for the proposal of the call
+			valid = valid.and_requires(proposal_tag);
+		} else if approvals.len() + 1 == threshold {
+			valid = valid.priority(T::ApprovalPriorityBoost::get());
+		}
+		valid.build()
+	}
+}
@@ -3,6 +3,9 @@
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
+
+pub mod extensions;
+pub use extensions::{CheckMultiAccountApproval, SponsorApprovalFees};
 #[cfg(test)]
 mod mock;
 
@@ -11,6 +14,20 @@ mod tests;
 
 //mod weights;
 
+/// Decides which accounts can be signatories of a multi-account, e.g. only accounts with a
+/// verified identity.
+pub trait SignatoryValidator<AccountId> {
+	/// Whether `who` can be registered as a signatory and approve calls.
+	fn is_valid(who: &AccountId) -> bool;
+}
+
+/// Every account can be a signatory.
+impl<AccountId> SignatoryValidator<AccountId> for () {
+	fn is_valid(_who: &AccountId) -> bool {
+		true
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 
@@ -25,10 +42,17 @@ pub mod pallet {
 	use frame_system::RawOrigin;
 
 	use frame_support::{
-		dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+		dispatch::{extract_actual_weight, Dispatchable, GetDispatchInfo, PostDispatchInfo},
+		storage::{with_transaction, TransactionOutcome},
+		traits::{
+			BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency, WithdrawReasons,
+		},
 		Parameter,
 	};
-	use sp_runtime::traits::TrailingZeroInput;
+	use sp_runtime::{
+		traits::{One, SaturatedConversion, Saturating, TrailingZeroInput, Zero},
+		transaction_validity::TransactionPriority,
+	};
 
 	// create an account for a set of signatories in this pallet
 	// set account nonce to 0
@@ -46,7 +70,191 @@ pub mod pallet {
 
 	type CallHash = [u8; 32];
 
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// A transfer the pallet makes from a multi-account every `interval` blocks.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ScheduledTransfer<AccountId, Balance, BlockNumber> {
+		/// Receiver of the payments.
+		pub dest: AccountId,
+		/// Amount of every payment.
+		pub amount: Balance,
+		/// Blocks between two payments.
+		pub interval: BlockNumber,
+		/// Payments left to make, `None` if the transfer repeats until it is cancelled.
+		pub remaining: Option<u32>,
+		/// The block the next payment is made in.
+		pub next: BlockNumber,
+		/// Reserved from the multi-account until the schedule completes or is cancelled.
+		pub deposit: Balance,
+	}
+
+	pub type ScheduledTransferOf<T> = ScheduledTransfer<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	/// Funds a multi-account releases to a beneficiary block by block. The part that has not
+	/// been claimed yet stays reserved on the multi-account.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Stream<AccountId, Balance, BlockNumber> {
+		/// The account the funds are released to.
+		pub beneficiary: AccountId,
+		/// Released every block between `start` and `end`.
+		pub rate: Balance,
+		/// The block the stream was created in.
+		pub start: BlockNumber,
+		/// The block after which nothing more is released.
+		pub end: BlockNumber,
+		/// How much the beneficiary claimed so far.
+		pub claimed: Balance,
+	}
+
+	pub type StreamOf<T> = Stream<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	/// Accounts that can freeze a multi-account and veto its proposals.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct GuardianSet<Guardians> {
+		/// The sorted guardians.
+		pub guardians: Guardians,
+		/// Votes of guardians needed to unfreeze the account.
+		pub unfreeze_threshold: u16,
+	}
+
+	/// Who takes over a multi-account when its signatories stop approving calls.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct InactivityRule<BlockNumber, Heirs> {
+		/// Blocks without any proposal or approval after which the heirs can claim the account.
+		pub inactivity_period: BlockNumber,
+		/// Blocks the signatories have to object to a claim, by proposing or approving any call.
+		pub challenge_period: BlockNumber,
+		/// The sorted accounts that become the signatories.
+		pub heirs: Heirs,
+		/// The threshold of the account once the heirs took over.
+		pub heir_threshold: u16,
+	}
+
+	/// An heir's claim on an inactive multi-account.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct InheritanceClaim<AccountId, BlockNumber> {
+		/// The heir that made the claim.
+		pub claimant: AccountId,
+		/// The first block the claim can be finalized in.
+		pub finalize_at: BlockNumber,
+	}
+
+	/// A vote of the other signatories to replace a signatory that lost its key.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Recovery<AccountId, Votes, BlockNumber> {
+		/// The key that takes over the seat of the lost one.
+		pub new: AccountId,
+		/// The sorted signatories that voted for the recovery.
+		pub votes: Votes,
+		/// The first block the recovery can be finalized in. Until then the lost key can cancel
+		/// it.
+		pub delay_end: BlockNumber,
+	}
+
+	/// How the approvals a pending call needs go down while it is stuck.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ThresholdDecay<BlockNumber> {
+		/// Blocks after its proposal until a call needs one approval less than the threshold.
+		pub decay_after: BlockNumber,
+		/// Blocks after which a call needs yet another approval less.
+		pub decay_step: BlockNumber,
+		/// The fewest approvals a call ever needs.
+		pub floor: u16,
+	}
+
+	pub type RecoveryOf<T> = Recovery<
+		<T as frame_system::Config>::AccountId,
+		BoundedVec<<T as frame_system::Config>::AccountId, <T as Config>::MaxSignatories>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	/// What the proposer tells the signers about a call.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ProposalMetadata<Title, Tags> {
+		/// A short, human readable title.
+		pub title: Title,
+		/// Hash of a longer description kept off chain, e.g. an IPFS content hash.
+		pub description_hash: Option<[u8; 32]>,
+		/// Free form labels.
+		pub tags: Tags,
+	}
+
+	pub type ProposalMetadataOf<T> = ProposalMetadata<
+		BoundedVec<u8, <T as Config>::MaxTitleLength>,
+		BoundedVec<BoundedVec<u8, <T as Config>::MaxTagLength>, <T as Config>::MaxTags>,
+	>;
+
+	/// When a call may be dispatched once it has enough approvals.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ProposalConditions<BlockNumber> {
+		/// A call of the same multi-account that has to be dispatched successfully first.
+		pub prerequisite: Option<CallHash>,
+		/// The first block the call can be dispatched in.
+		pub not_before: Option<BlockNumber>,
+		/// The last block the call can be dispatched in.
+		pub not_after: Option<BlockNumber>,
+	}
+
+	/// The outcome of dispatching a call that reached its threshold.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ExecutionReceipt<BlockNumber, Balance> {
+		/// The block of the last attempt.
+		pub at: BlockNumber,
+		/// How often dispatching the call was attempted.
+		pub attempts: u32,
+		/// The result of the last attempt.
+		pub result: DispatchResult,
+		/// Reserved from the multi-account until the receipt is removed.
+		pub deposit: Balance,
+	}
+
+	pub type ExecutionReceiptOf<T> =
+		ExecutionReceipt<<T as frame_system::Config>::BlockNumber, BalanceOf<T>>;
+
+	/// Why a call with enough approvals is not dispatched.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum Blocker<BlockNumber> {
+		/// The prerequisite call has not been dispatched successfully yet.
+		PrerequisiteNotExecuted(CallHash),
+		/// The call can't be dispatched before this block.
+		TooEarly(BlockNumber),
+		/// The call could only be dispatched up to this block.
+		TooLate(BlockNumber),
+	}
+
+	/// A call that was proposed with `account_create_call` and has not been dispatched yet.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ProposalInfo<AccountId, Balance> {
+		/// The signatory that proposed the call.
+		pub proposer: AccountId,
+		/// Reserved from the proposer until the call is dispatched or the account is closed.
+		pub deposit: Balance,
+		/// `spec_version` of the runtime the call was proposed under.
+		pub spec_version: u32,
+		/// `transaction_version` of the runtime the call was proposed under. If it changes, the
+		/// encoded call may no longer mean the same thing.
+		pub transaction_version: u32,
+		/// Set by a runtime upgrade that changed the `transaction_version`. Stale proposals can't
+		/// be approved anymore and can be proposed again.
+		pub stale: bool,
+	}
+
+	/// The in-code storage version. Version 1 counts the proposals made before the pending
+	/// limits existed into `PendingProposals` and `PendingBySignatory`.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -63,6 +271,83 @@ pub mod pallet {
 			+ GetDispatchInfo
 			+ From<frame_system::Call<Self>>;
 		type MaxSignatories: Get<u32>;
+		/// The largest encoded call whose preimage is kept on chain so that signers can inspect
+		/// it. Bigger calls can still be proposed, only their hash is stored.
+		#[pallet::constant]
+		type MaxCallSize: Get<u32>;
+		/// The currency multi-accounts pay their signatories' approval fees in, and deposits are
+		/// reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Reserved from whoever registers a multi-account, until the account is closed.
+		#[pallet::constant]
+		type RegistrationDeposit: Get<BalanceOf<Self>>;
+		/// Reserved from the proposer of a call, until the call is dispatched.
+		#[pallet::constant]
+		type ProposalDeposit: Get<BalanceOf<Self>>;
+		/// Reserved from the proposer for every byte of a preimage kept in `CallData`, together
+		/// with the proposal deposit.
+		#[pallet::constant]
+		type PreimageDepositPerByte: Get<BalanceOf<Self>>;
+		/// The most scheduled transfers that are paid out in a single block.
+		#[pallet::constant]
+		type MaxScheduledPerBlock: Get<u32>;
+		/// Reserved from a multi-account for every scheduled transfer, until it completes or is
+		/// cancelled.
+		#[pallet::constant]
+		type ScheduleDeposit: Get<BalanceOf<Self>>;
+		/// The most scheduled transfers a multi-account can have at once.
+		#[pallet::constant]
+		type MaxSchedulesPerAccount: Get<u32>;
+		/// Added to the priority of the approval that reaches a call's threshold.
+		#[pallet::constant]
+		type ApprovalPriorityBoost: Get<TransactionPriority>;
+		/// The longest title of a proposal, in bytes.
+		#[pallet::constant]
+		type MaxTitleLength: Get<u32>;
+		/// The most tags a proposal can have.
+		#[pallet::constant]
+		type MaxTags: Get<u32>;
+		/// The longest tag of a proposal, in bytes.
+		#[pallet::constant]
+		type MaxTagLength: Get<u32>;
+		/// Reserved from the proposer for every byte of proposal metadata, until the call is
+		/// dispatched.
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+		/// The most calls a multi-account can have pending at once.
+		#[pallet::constant]
+		type MaxPendingPerAccount: Get<u32>;
+		/// The most pending calls a single signatory can have proposed for a multi-account.
+		#[pallet::constant]
+		type MaxPendingPerSignatory: Get<u32>;
+		/// Blocks after which a call that is still pending can be removed with `expire_proposal`.
+		#[pallet::constant]
+		type ProposalLifetime: Get<Self::BlockNumber>;
+		/// The most approvals a single `approve_many` can carry.
+		#[pallet::constant]
+		type MaxApprovalBatch: Get<u32>;
+		/// The number of sub-accounts a multi-account can use, indexed from zero. Closing an
+		/// account checks all of them for funds.
+		#[pallet::constant]
+		type MaxSubAccounts: Get<u16>;
+		/// How often dispatching a call that reached its threshold is attempted before it is
+		/// given up. One means a failed call is not retried.
+		#[pallet::constant]
+		type MaxExecutionAttempts: Get<u32>;
+		/// Reserved from a multi-account when one of its calls is dispatched for the first time,
+		/// until the receipt of the dispatch is removed with `remove_receipt`.
+		#[pallet::constant]
+		type ReceiptDeposit: Get<BalanceOf<Self>>;
+		/// Blocks after the last attempt to dispatch a call until its receipt can be removed.
+		#[pallet::constant]
+		type ReceiptLifetime: Get<Self::BlockNumber>;
+		/// Blocks between starting the recovery of a lost signatory key and replacing it, during
+		/// which the supposedly lost key can cancel the recovery.
+		#[pallet::constant]
+		type RecoveryDelay: Get<Self::BlockNumber>;
+		/// Checked for every signatory when an account is registered, and for the signer of
+		/// every proposal and approval. `()` accepts everyone.
+		type SignatoryValidator: SignatoryValidator<Self::AccountId>;
 	}
 
 	#[pallet::storage]
@@ -101,16 +386,469 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
-	/// This is a storage item for executed calls
+	/// The SCALE encoded call behind a pending call hash, kept so that approvers can see what
+	/// they are signing. Removed once the call is dispatched.
+	#[pallet::storage]
+	#[pallet::getter(fn get_call_data)]
+	pub type CallData<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		BoundedVec<u8, T::MaxCallSize>,
+		OptionQuery,
+	>;
+
+	/// The account that registered a multi-account and the deposit reserved from it.
+	#[pallet::storage]
+	#[pallet::getter(fn registrar)]
+	pub type Registrar<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// The proposer and deposit of every call that was proposed and is still pending.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal)]
+	pub type Proposals<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		ProposalInfo<T::AccountId, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// The block every pending call was proposed in, which its threshold decays from and its
+	/// `ProposalLifetime` counts from.
+	#[pallet::storage]
+	#[pallet::getter(fn proposed_at)]
+	pub type ProposedAt<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// The threshold decay a multi-account opted into.
+	#[pallet::storage]
+	#[pallet::getter(fn threshold_decay)]
+	pub type ThresholdDecays<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ThresholdDecay<T::BlockNumber>, OptionQuery>;
+
+	/// Recurring transfers of a multi-account, by schedule index.
+	#[pallet::storage]
+	#[pallet::getter(fn schedule)]
+	pub type Schedules<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		u32,
+		ScheduledTransferOf<T>,
+		OptionQuery,
+	>;
+
+	/// The index the next scheduled transfer gets.
+	#[pallet::storage]
+	pub type NextScheduleIndex<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// How many scheduled transfers a multi-account has.
+	#[pallet::storage]
+	#[pallet::getter(fn schedule_count)]
+	pub type ScheduleCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The scheduled transfers that are paid out in a block.
+	#[pallet::storage]
+	pub type Agenda<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<(T::AccountId, u32), T::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+
+	/// Payout streams of a multi-account, by stream index.
+	#[pallet::storage]
+	#[pallet::getter(fn stream)]
+	pub type Streams<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		u32,
+		StreamOf<T>,
+		OptionQuery,
+	>;
+
+	/// The index the next stream gets.
+	#[pallet::storage]
+	pub type NextStreamIndex<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The guardians of a multi-account, if it has any.
+	#[pallet::storage]
+	#[pallet::getter(fn guardians)]
+	pub type Guardians<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		GuardianSet<BoundedVec<T::AccountId, T::MaxSignatories>>,
+		OptionQuery,
+	>;
+
+	/// Multi-accounts a guardian froze, with the guardians that voted to unfreeze them so far.
+	#[pallet::storage]
+	#[pallet::getter(fn unfreeze_votes)]
+	pub type Frozen<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<T::AccountId, T::MaxSignatories>,
+		OptionQuery,
+	>;
+
+	/// The last block a signatory proposed or approved a call of the multi-account in.
+	#[pallet::storage]
+	#[pallet::getter(fn last_activity)]
+	pub type LastActivity<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, ValueQuery>;
+
+	/// The inactivity rule of a multi-account, if it set one.
+	#[pallet::storage]
+	#[pallet::getter(fn inactivity_rule)]
+	pub type InactivityRules<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		InactivityRule<T::BlockNumber, BoundedVec<T::AccountId, T::MaxSignatories>>,
+		OptionQuery,
+	>;
+
+	/// How many proposals of a multi-account have not been dispatched, vetoed, cancelled or expired
+	/// yet.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_proposals)]
+	pub type PendingProposals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// How many of the pending proposals of a multi-account each signatory made.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_by_signatory)]
+	pub type PendingBySignatory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Sub-accounts a multi-account has dispatched calls from, by their index.
 	#[pallet::storage]
-	pub type Executed<T: Config> = StorageMap<_, Blake2_128Concat, CallHash, (), ValueQuery>;
+	#[pallet::getter(fn sub_account)]
+	pub type SubAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		u16,
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	/// The metadata of a pending call and the deposit reserved for it from the proposer.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_metadata)]
+	pub type Metadata<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		(ProposalMetadataOf<T>, BalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// Calls that were proposed as a salted commitment, with the last block the call can be
+	/// revealed in. The approvals are kept in `Calls` under the commitment.
+	#[pallet::storage]
+	#[pallet::getter(fn commitment_deadline)]
+	pub type Commitments<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// Votes signatories handed to another account: multi-account -> signatory -> (delegate, last
+	/// block the delegate can approve in).
+	#[pallet::storage]
+	#[pallet::getter(fn delegation)]
+	pub type Delegations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(T::AccountId, T::BlockNumber),
+		OptionQuery,
+	>;
+
+	/// A pending claim of an heir on an inactive multi-account.
+	#[pallet::storage]
+	#[pallet::getter(fn inheritance_claim)]
+	pub type InheritanceClaims<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		InheritanceClaim<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Pending recoveries of lost signatory keys: multi-account -> lost signatory -> recovery.
+	#[pallet::storage]
+	#[pallet::getter(fn recovery)]
+	pub type Recoveries<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		RecoveryOf<T>,
+		OptionQuery,
+	>;
+
+	/// How much a multi-account is still willing to pay of its signatories' fees for creating
+	/// and approving its calls. See [`SponsorApprovalFees`].
+	#[pallet::storage]
+	#[pallet::getter(fn fee_budget)]
+	pub type FeeBudget<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// When and with which result the calls of a multi-account that reached their threshold
+	/// were dispatched. Other proposals can depend on a call that succeeded.
+	#[pallet::storage]
+	#[pallet::getter(fn executed)]
+	pub type Executed<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		ExecutionReceiptOf<T>,
+		OptionQuery,
+	>;
+
+	/// The sum of the receipt deposits reserved from a multi-account.
+	#[pallet::storage]
+	pub type ReceiptDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The `transaction_version` the proposals were last checked against on a runtime upgrade.
+	#[pallet::storage]
+	pub type LastTransactionVersion<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The raw key of the last proposal the sweep for stale proposals visited, empty if it
+	/// hasn't visited any yet. `None` while no sweep is running.
+	#[pallet::storage]
+	pub type StaleSweep<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<256>>, OptionQuery>;
+
+	/// When a pending call may be dispatched, if its proposer restricted it.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_conditions)]
+	pub type Conditions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		CallHash,
+		ProposalConditions<T::BlockNumber>,
+		OptionQuery,
+	>;
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		Account { id: T::AccountId, signatories: Vec<T::AccountId>, threshold: u16 },
-		Approval { id: T::AccountId, signer: T::AccountId, hash: CallHash },
+		/// A signatory proposed a new call. The proposer's own `Approval` follows.
+		Proposal { id: T::AccountId, proposer: T::AccountId, hash: CallHash },
+		Approval {
+			id: T::AccountId,
+			signer: T::AccountId,
+			hash: CallHash,
+			metadata: Option<ProposalMetadataOf<T>>,
+		},
 		Call { id: T::AccountId, hash: CallHash, signatories: Vec<T::AccountId> },
+		/// The multi-account set how much it pays of its signatories' fees.
+		FeeBudgetSet { id: T::AccountId, budget: BalanceOf<T> },
+		/// The multi-account paid the fee of a signatory creating or approving one of its calls.
+		FeePaid { id: T::AccountId, signer: T::AccountId, amount: BalanceOf<T> },
+		/// A runtime upgrade changed the transaction version, so the pending call can't be
+		/// approved anymore. Its deposit was returned.
+		ProposalStale { id: T::AccountId, hash: CallHash },
+		/// The multi-account scheduled a recurring transfer.
+		TransferScheduled {
+			id: T::AccountId,
+			index: u32,
+			dest: T::AccountId,
+			amount: BalanceOf<T>,
+			interval: T::BlockNumber,
+			count: Option<u32>,
+		},
+		/// A scheduled payment was attempted. Failed payments are retried after the next interval.
+		ScheduledTransferExecuted { id: T::AccountId, index: u32, result: DispatchResult },
+		/// All payments of a scheduled transfer were made.
+		ScheduleCompleted { id: T::AccountId, index: u32 },
+		/// A scheduled transfer was cancelled by the multi-account, or dropped because no block
+		/// near its next payment had room for it.
+		ScheduleCancelled { id: T::AccountId, index: u32 },
+		/// The multi-account reserved `rate * (end - start)` to release it to `beneficiary`.
+		StreamCreated {
+			id: T::AccountId,
+			index: u32,
+			beneficiary: T::AccountId,
+			rate: BalanceOf<T>,
+			start: T::BlockNumber,
+			end: T::BlockNumber,
+		},
+		/// The beneficiary claimed what the stream released so far.
+		StreamClaimed { id: T::AccountId, index: u32, beneficiary: T::AccountId, amount: BalanceOf<T> },
+		/// The multi-account cancelled a stream. What was released but not claimed yet was
+		/// `paid` to the beneficiary, the rest was `refunded` to the multi-account.
+		StreamCancelled { id: T::AccountId, index: u32, paid: BalanceOf<T>, refunded: BalanceOf<T> },
+		/// The multi-account replaced its guardians. An empty set removes them.
+		GuardiansSet { id: T::AccountId, guardians: Vec<T::AccountId>, unfreeze_threshold: u16 },
+		/// A guardian froze the multi-account.
+		AccountFrozen { id: T::AccountId, guardian: T::AccountId },
+		/// A guardian voted to unfreeze the multi-account.
+		UnfreezeVote { id: T::AccountId, guardian: T::AccountId },
+		/// Enough guardians voted, the multi-account is unfrozen.
+		AccountUnfrozen { id: T::AccountId },
+		/// A guardian vetoed a pending call, which was removed.
+		ProposalVetoed { id: T::AccountId, hash: CallHash, guardian: T::AccountId },
+		/// The multi-account set or removed its inactivity rule.
+		InactivityRuleSet { id: T::AccountId, heirs: Vec<T::AccountId>, heir_threshold: u16 },
+		/// An heir claimed an inactive multi-account. Any proposal or approval before
+		/// `finalize_at` cancels the claim.
+		InheritanceClaimed { id: T::AccountId, heir: T::AccountId, finalize_at: T::BlockNumber },
+		/// A signatory was active during the challenge period, so the claim was dropped.
+		InheritanceClaimCancelled { id: T::AccountId },
+		/// The heirs took over the multi-account. Its pending calls were removed.
+		InheritanceFinalized { id: T::AccountId, signatories: Vec<T::AccountId>, threshold: u16 },
+		/// A signatory let `delegate` approve calls in its name until block `until`.
+		VoteDelegated {
+			id: T::AccountId,
+			signer: T::AccountId,
+			delegate: T::AccountId,
+			until: T::BlockNumber,
+		},
+		/// A signatory took its vote back before the delegation expired.
+		DelegationRevoked { id: T::AccountId, signer: T::AccountId, delegate: T::AccountId },
+		/// `delegate` approved a call in the name of `signer`. The `Approval` event for `signer`
+		/// follows.
+		DelegatedApproval {
+			id: T::AccountId,
+			signer: T::AccountId,
+			delegate: T::AccountId,
+			hash: CallHash,
+		},
+		/// The proposer set or changed the metadata of a pending call.
+		MetadataSet { id: T::AccountId, hash: CallHash, metadata: ProposalMetadataOf<T> },
+		/// A signatory revealed the call behind `commitment` and it was dispatched.
+		CommitmentRevealed { id: T::AccountId, commitment: CallHash, hash: CallHash },
+		/// The call behind `commitment` was not revealed before its deadline and was removed.
+		CommitmentExpired { id: T::AccountId, commitment: CallHash },
+		/// The proposer restricted when the pending call can be dispatched.
+		ConditionsSet {
+			id: T::AccountId,
+			hash: CallHash,
+			conditions: ProposalConditions<T::BlockNumber>,
+		},
+		/// The call has enough approvals but its conditions don't hold yet. It can be dispatched
+		/// with `execute_deferred` once they do.
+		DispatchDeferred { id: T::AccountId, hash: CallHash, reason: Blocker<T::BlockNumber> },
+		/// The approval at `index` of an `approve_many` batch succeeded or failed. A failed
+		/// approval left no trace, the rest of the batch went on.
+		BatchApproval { index: u32, id: T::AccountId, hash: CallHash, result: DispatchResult },
+		/// The multi-account dispatched a call from its sub-account `index`.
+		SubAccountCall { id: T::AccountId, index: u16, sub_account: T::AccountId },
+		/// A call that reached its threshold was dispatched. If it failed, the approvals are kept
+		/// and it can be retried with `retry_execution` until `MaxExecutionAttempts` is reached.
+		CallExecuted { id: T::AccountId, hash: CallHash, attempt: u32, result: DispatchResult },
+		/// A signatory started replacing the lost key `lost` with `new`. The lost key can cancel
+		/// until `delay_end`.
+		RecoveryInitiated {
+			id: T::AccountId,
+			lost: T::AccountId,
+			new: T::AccountId,
+			initiator: T::AccountId,
+			delay_end: T::BlockNumber,
+		},
+		/// A signatory voted for the recovery of `lost`.
+		RecoveryVote { id: T::AccountId, lost: T::AccountId, voter: T::AccountId },
+		/// The supposedly lost key cancelled its recovery.
+		RecoveryCancelled { id: T::AccountId, lost: T::AccountId },
+		/// `new` took over the seat of `lost`, including its approvals of pending calls.
+		SignatoryRecovered { id: T::AccountId, lost: T::AccountId, new: T::AccountId },
+		/// The multi-account set or removed the decay of its threshold for stuck calls.
+		ThresholdDecaySet { id: T::AccountId, decay: Option<ThresholdDecay<T::BlockNumber>> },
+		/// The proposer withdrew a pending call.
+		ProposalCancelled { id: T::AccountId, hash: CallHash },
+		/// A pending call was removed because the multi-account was handed over to its heirs or
+		/// closed.
+		ProposalRemoved { id: T::AccountId, hash: CallHash },
+		/// A pending call whose block window closed or that outlived `ProposalLifetime` was
+		/// removed.
+		ProposalExpired { id: T::AccountId, hash: CallHash },
+		/// The receipt of a dispatched call was removed and its deposit returned. The call can be
+		/// proposed again.
+		ReceiptRemoved { id: T::AccountId, hash: CallHash },
+		/// The multi-account was closed and its free balance was sent to `dest`.
+		AccountClosed { id: T::AccountId, dest: T::AccountId, amount: BalanceOf<T> },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Make the scheduled payments that are due in this block.
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let agenda = <Agenda<T>>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			for (id, index) in agenda {
+				weight = weight.saturating_add(Self::pay_scheduled(now, id, index));
+			}
+			weight
+		}
+
+		/// Pending calls were encoded for the runtime they were proposed under. When an upgrade
+		/// changes the transaction version they may decode differently, so a sweep is started
+		/// that marks them stale and returns their deposits over the next blocks. Until it
+		/// reaches a proposal, `is_stale` already keeps it from being approved.
+		///
+		/// Proposals made before the pending limits existed are counted once, see
+		/// `migrate_pending_counters`.
+		fn on_runtime_upgrade() -> Weight {
+			let weight = Self::migrate_pending_counters();
+			let transaction_version = T::Version::get().transaction_version;
+			if <LastTransactionVersion<T>>::get() == transaction_version {
+				return weight.saturating_add(T::DbWeight::get().reads(1))
+			}
+			<LastTransactionVersion<T>>::put(transaction_version);
+			<StaleSweep<T>>::put(BoundedVec::default());
+			weight.saturating_add(T::DbWeight::get().reads_writes(1, 2))
+		}
+
+		/// Continue the sweep for stale proposals with the weight left in the block.
+		fn on_idle(_now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::sweep_stale_proposals(remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -125,9 +863,18 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			ensure!(threshold >= 1, Error::<T>::MinimumThreshold);
+			ensure!(!<Threshold<T>>::contains_key(&id), Error::<T>::AccountAlreadyExists);
 			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+			ensure!(
+				signatories.iter().all(T::SignatoryValidator::is_valid),
+				Error::<T>::SignatoryNotVerified
+			);
 			let bounded_vec =
 				BoundedVec::try_from(signatories).map_err(|_| Error::<T>::TooManySignatories)?;
+			let deposit = T::RegistrationDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			<Registrar<T>>::insert(&id, (who, deposit));
+			<LastActivity<T>>::insert(&id, frame_system::Pallet::<T>::block_number());
 			<Account<T>>::insert(&id, &bounded_vec);
 			<Threshold<T>>::insert(&id, &threshold);
 			Self::deposit_event(Event::Account {
@@ -146,13 +893,22 @@ pub mod pallet {
 			call: Box<<T as Config>::RuntimeCall>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
 			let signatories = <Account<T>>::get(&id);
 			ensure!(
 				signatories.into_inner().binary_search(&who).is_ok(),
 				Error::<T>::SignerIsNotApproved
 			);
+			ensure!(T::SignatoryValidator::is_valid(&who), Error::<T>::SignatoryNotVerified);
 			let hash = call.using_encoded(sp_io::hashing::blake2_256);
 
+			// a stale proposal of the same call is replaced
+			if Self::is_stale(&id, &hash) {
+				<Calls<T>>::remove(&id, &hash);
+				<CallData<T>>::remove(&id, &hash);
+				<ProposedAt<T>>::remove(&id, &hash);
+				Self::remove_proposal(&id, &hash);
+			}
 			//check if hash already exists
 			let calls = <Calls<T>>::get(&id, &hash);
 			ensure!(calls.is_empty(), Error::<T>::CallHashAlreadyExists);
@@ -161,8 +917,32 @@ pub mod pallet {
 			// into the vec
 			let approvals = BoundedVec::try_from(vec![who.clone()])
 				.map_err(|_| Error::<T>::TooManySignatories)?;
+			Self::add_pending(&id, &who)?;
+			// keep the preimage around for the signers if it is small enough, paid for by the
+			// proposer
+			let data = BoundedVec::<u8, T::MaxCallSize>::try_from(call.encode()).ok();
+			let preimage_deposit = data.as_ref().map_or(Zero::zero(), |data| {
+				T::PreimageDepositPerByte::get().saturating_mul((data.len() as u32).into())
+			});
+			let deposit = T::ProposalDeposit::get().saturating_add(preimage_deposit);
+			T::Currency::reserve(&who, deposit)?;
+			let proposal = ProposalInfo {
+				proposer: who.clone(),
+				deposit,
+				spec_version: T::Version::get().spec_version,
+				transaction_version: T::Version::get().transaction_version,
+				stale: false,
+			};
+			<Proposals<T>>::insert(&id, &hash, proposal);
+			<ProposedAt<T>>::insert(&id, &hash, frame_system::Pallet::<T>::block_number());
 			<Calls<T>>::insert(&id, &hash, approvals);
-			Self::deposit_event(Event::Approval { id, signer: who, hash });
+			if let Some(data) = data {
+				<CallData<T>>::insert(&id, &hash, data);
+			}
+			Self::deposit_event(Event::Proposal { id: id.clone(), proposer: who.clone(), hash });
+			Self::note_activity(&id);
+			Self::remove_expired_delegation(&id, &who);
+			Self::deposit_event(Event::Approval { id, signer: who, hash, metadata: None });
 			Ok(())
 		}
 
@@ -174,61 +954,1173 @@ pub mod pallet {
 			call: Box<<T as Config>::RuntimeCall>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let signatories = <Account<T>>::get(&id);
+			Self::do_approve(id, who, call)?;
+			Ok(().into())
+		}
+
+		/// Set how much the multi-account pays of its signatories' fees for creating and
+		/// approving its calls. Has to be dispatched by the multi-account itself, i.e. through
+		/// a call its signatories approved.
+		#[pallet::call_index(3)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_fee_budget(origin: OriginFor<T>, budget: BalanceOf<T>) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
+			<FeeBudget<T>>::insert(&id, budget);
+			Self::deposit_event(Event::FeeBudgetSet { id, budget });
+			Ok(())
+		}
+
+		/// Close the multi-account: send its whole free balance to `dest`, return the
+		/// registration deposit and the deposits of all pending proposals, and remove the account
+		/// and its calls. Has to be dispatched by the multi-account itself, i.e. through a call
+		/// its signatories approved.
+		///
+		/// Fails if any of the account's funds are locked. The receipts of dispatched calls have
+		/// to be removed with `remove_receipt` first, so that everything left to remove is
+		/// bounded by the pending calls, signatories and sub-accounts it can have.
+		#[pallet::call_index(4)]
+		#[pallet::weight({
+			let pending = T::MaxPendingPerAccount::get() as u64;
+			let signatories = T::MaxSignatories::get() as u64;
+			let sub_accounts = T::MaxSubAccounts::get() as u64;
+			Weight::from_parts(50_000, 0) +
+				T::DbWeight::get().reads_writes(
+					2 * pending + 2 * signatories + 2 * sub_accounts + 12,
+					8 * pending + 2 * signatories + sub_accounts + 12,
+				)
+		})]
+		pub fn close_account(origin: OriginFor<T>, dest: T::AccountId) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
 			ensure!(
-				signatories.into_inner().binary_search(&who).is_ok(),
-				Error::<T>::SignerIsNotApproved
+				<Schedules<T>>::iter_prefix_values(&id).next().is_none(),
+				Error::<T>::HasPendingSchedules
 			);
-			let approvals_needed = <Threshold<T>>::get(&id);
-			let mut signers: Vec<_> = Vec::new();
-			let mut number_of_approvals = 0;
-			let hash = &call.using_encoded(sp_io::hashing::blake2_256);
-			// ensure!(
-			//     <Calls<T>>::contains_key(&id, hash),
-			//     Error::<T>::NotFound
-			// );
-			<Calls<T>>::try_mutate(&id, hash, |sig| -> DispatchResultWithPostInfo {
-				// if the number of approvals needed has passed and the call has been dispatched
-				// there is no need to add 32 bytes in storage that is of no use
-				// so we return early here
-				if sig.as_slice().len() as u16 == approvals_needed {
-					return Err(Error::<T>::DispatchHasAlreadyOccured.into());
-				};
-				// the ensure_sorted_and_insert already makes a check to confirm if an account id
-				// already exists in the bounded vec. so we can be sure that a double vote will not
-				// occur.
-				number_of_approvals = sig.as_slice().len() as u16;
-				let sorted_vec =
-					Self::ensure_sorted_and_insert(sig.as_slice().to_vec(), who.clone())?;
-				signers.extend(sorted_vec.clone());
-				*sig =
-					BoundedVec::try_from(sorted_vec).map_err(|_| Error::<T>::TooManySignatories)?;
-				Ok(().into())
-			})?;
+			ensure!(
+				<Streams<T>>::iter_prefix_values(&id).next().is_none(),
+				Error::<T>::HasActiveStreams
+			);
+			ensure!(<Executed<T>>::iter_key_prefix(&id).next().is_none(), Error::<T>::HasReceipts);
+			// the funds of sub-accounts could not be reached anymore. funds can be sent to a
+			// sub-account before it is used, so every index is checked, not only the recorded ones
+			ensure!(
+				(0..T::MaxSubAccounts::get()).all(|index| {
+					T::Currency::total_balance(&Self::sub_account_id(&id, index)).is_zero()
+				}),
+				Error::<T>::SubAccountHasFunds
+			);
+			T::Currency::unreserve(&id, <ReceiptDeposits<T>>::take(&id));
+			let amount = T::Currency::free_balance(&id);
+			T::Currency::ensure_can_withdraw(
+				&id,
+				amount,
+				WithdrawReasons::TRANSFER,
+				Zero::zero(),
+			)
+			.map_err(|_| Error::<T>::AccountHasLocks)?;
 
-			Self::deposit_event(Event::Approval {
-				id: id.clone(),
-				signer: who.clone(),
-				hash: hash.clone(),
-			});
+			if let Some((depositor, deposit)) = <Registrar<T>>::take(&id) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			Self::clear_pending_calls(&id);
+			<Account<T>>::remove(&id);
+			<Threshold<T>>::remove(&id);
+			<FeeBudget<T>>::remove(&id);
+			<Guardians<T>>::remove(&id);
+			<LastActivity<T>>::remove(&id);
+			<InactivityRules<T>>::remove(&id);
+			<InheritanceClaims<T>>::remove(&id);
+			<ThresholdDecays<T>>::remove(&id);
+			let _ = <Delegations<T>>::clear_prefix(&id, u32::MAX, None);
+			let _ = <Recoveries<T>>::clear_prefix(&id, u32::MAX, None);
+			let _ = <SubAccounts<T>>::clear_prefix(&id, u32::MAX, None);
 
-			if (number_of_approvals + 1) == approvals_needed {
-				//call.dispatch(<T as frame_system::Config>::RuntimeOrigin::signed(id));
-				let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
-				result.map_err(|err| {
-					return err;
-				})?;
-				Self::deposit_event(Event::Call {
-					id: id.clone(),
-					signatories: signers,
-					hash: hash.clone(),
+			if !amount.is_zero() {
+				T::Currency::transfer(&id, &dest, amount, ExistenceRequirement::AllowDeath)?;
+			}
+			Self::deposit_event(Event::AccountClosed { id, dest, amount });
+			Ok(())
+		}
+
+		/// Pay `amount` to `dest` every `interval` blocks, `count` times or until the schedule is
+		/// cancelled if `count` is `None`. The first payment is made `interval` blocks from now.
+		/// Has to be dispatched by the multi-account itself, i.e. through a call its signatories
+		/// approved, so that every payment doesn't need an approval round of its own.
+		#[pallet::call_index(5)]
+		#[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn schedule_transfer(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			amount: BalanceOf<T>,
+			interval: T::BlockNumber,
+			count: Option<u32>,
+		) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
+			ensure!(!interval.is_zero() && count != Some(0), Error::<T>::InvalidSchedule);
+			ensure!(
+				<ScheduleCount<T>>::get(&id) < T::MaxSchedulesPerAccount::get(),
+				Error::<T>::TooManySchedules
+			);
+			let index = <NextScheduleIndex<T>>::try_mutate(|next| {
+				let index = *next;
+				*next = next.checked_add(1).ok_or(Error::<T>::ScheduleIndexOverflow)?;
+				Ok::<_, Error<T>>(index)
+			})?;
+			let deposit = T::ScheduleDeposit::get();
+			T::Currency::reserve(&id, deposit)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let next = Self::enqueue(now.saturating_add(interval), (id.clone(), index))
+				.ok_or(Error::<T>::AgendaFull)?;
+			<ScheduleCount<T>>::mutate(&id, |count| *count = count.saturating_add(1));
+			let schedule = ScheduledTransfer {
+				dest: dest.clone(),
+				amount,
+				interval,
+				remaining: count,
+				next,
+				deposit,
+			};
+			<Schedules<T>>::insert(&id, index, schedule);
+			Self::deposit_event(Event::TransferScheduled { id, index, dest, amount, interval, count });
+			Ok(())
+		}
+
+		/// Stop a scheduled transfer of the multi-account. Has to be dispatched by the
+		/// multi-account itself.
+		#[pallet::call_index(6)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+		pub fn cancel_schedule(origin: OriginFor<T>, index: u32) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			let schedule = <Schedules<T>>::take(&id, index).ok_or(Error::<T>::ScheduleNotFound)?;
+			<Agenda<T>>::mutate(schedule.next, |agenda| {
+				agenda.retain(|(account, scheduled)| *account != id || *scheduled != index)
+			});
+			Self::release_schedule(&id, &schedule);
+			Self::deposit_event(Event::ScheduleCancelled { id, index });
+			Ok(())
+		}
+
+		/// Release `rate` to `beneficiary` every block for the next `duration` blocks. The whole
+		/// amount is reserved on the multi-account up front. Has to be dispatched by the
+		/// multi-account itself, i.e. through a call its signatories approved.
+		#[pallet::call_index(7)]
+		#[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn create_stream(
+			origin: OriginFor<T>,
+			beneficiary: T::AccountId,
+			rate: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
+			ensure!(!rate.is_zero() && !duration.is_zero(), Error::<T>::InvalidStream);
+			T::Currency::reserve(&id, rate.saturating_mul(Self::blocks_to_balance(duration)))?;
+			let index = <NextStreamIndex<T>>::try_mutate(|next| {
+				let index = *next;
+				*next = next.checked_add(1).ok_or(Error::<T>::StreamIndexOverflow)?;
+				Ok::<_, Error<T>>(index)
+			})?;
+			let start = frame_system::Pallet::<T>::block_number();
+			let end = start.saturating_add(duration);
+			let stream = Stream {
+				beneficiary: beneficiary.clone(),
+				rate,
+				start,
+				end,
+				claimed: Zero::zero(),
+			};
+			<Streams<T>>::insert(&id, index, stream);
+			Self::deposit_event(Event::StreamCreated { id, index, beneficiary, rate, start, end });
+			Ok(())
+		}
+
+		/// Claim what the stream `index` of the multi-account `id` released since the last claim.
+		/// Only the beneficiary of the stream can claim.
+		#[pallet::call_index(8)]
+		#[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn claim_stream(origin: OriginFor<T>, id: T::AccountId, index: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			let mut stream = <Streams<T>>::get(&id, index).ok_or(Error::<T>::StreamNotFound)?;
+			ensure!(stream.beneficiary == who, Error::<T>::NotStreamBeneficiary);
+			let now = frame_system::Pallet::<T>::block_number();
+			let amount = Self::released(&stream, now).saturating_sub(stream.claimed);
+			ensure!(!amount.is_zero(), Error::<T>::NothingToClaim);
+			T::Currency::repatriate_reserved(&id, &who, amount, BalanceStatus::Free)?;
+			stream.claimed = stream.claimed.saturating_add(amount);
+			if now >= stream.end {
+				<Streams<T>>::remove(&id, index);
+			} else {
+				<Streams<T>>::insert(&id, index, stream);
+			}
+			Self::deposit_event(Event::StreamClaimed { id, index, beneficiary: who, amount });
+			Ok(())
+		}
+
+		/// Stop a stream of the multi-account. What it released so far and the beneficiary has
+		/// not claimed yet is paid out, the rest is returned to the multi-account. Has to be
+		/// dispatched by the multi-account itself.
+		#[pallet::call_index(9)]
+		#[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn cancel_stream(origin: OriginFor<T>, index: u32) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			let stream = <Streams<T>>::take(&id, index).ok_or(Error::<T>::StreamNotFound)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let released = Self::released(&stream, now);
+			let paid = released.saturating_sub(stream.claimed);
+			if !paid.is_zero() {
+				T::Currency::repatriate_reserved(
+					&id,
+					&stream.beneficiary,
+					paid,
+					BalanceStatus::Free,
+				)?;
+			}
+			let total = stream.rate.saturating_mul(Self::blocks_to_balance(
+				stream.end.saturating_sub(stream.start),
+			));
+			let refunded = total.saturating_sub(released);
+			T::Currency::unreserve(&id, refunded);
+			Self::deposit_event(Event::StreamCancelled { id, index, paid, refunded });
+			Ok(())
+		}
+
+		/// Replace the guardians of the multi-account. Any single guardian can freeze the account
+		/// and veto its pending calls, and `unfreeze_threshold` of them have to agree to unfreeze
+		/// it. An empty list removes the guardians. Has to be dispatched by the multi-account
+		/// itself, i.e. through a call its signatories approved.
+		#[pallet::call_index(10)]
+		#[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_guardians(
+			origin: OriginFor<T>,
+			mut guardians: Vec<T::AccountId>,
+			unfreeze_threshold: u16,
+		) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
+			guardians.sort();
+			guardians.dedup();
+			if guardians.is_empty() {
+				<Guardians<T>>::remove(&id);
+			} else {
+				ensure!(
+					unfreeze_threshold >= 1 && unfreeze_threshold as usize <= guardians.len(),
+					Error::<T>::InvalidGuardians
+				);
+				let set = GuardianSet {
+					guardians: BoundedVec::try_from(guardians.clone())
+						.map_err(|_| Error::<T>::InvalidGuardians)?,
+					unfreeze_threshold,
+				};
+				<Guardians<T>>::insert(&id, set);
+			}
+			// votes of guardians that were removed no longer count towards unfreezing the account
+			<Frozen<T>>::mutate(&id, |votes| {
+				if let Some(votes) = votes {
+					votes.retain(|voter| guardians.binary_search(voter).is_ok());
+				}
+			});
+			Self::deposit_event(Event::GuardiansSet { id, guardians, unfreeze_threshold });
+			Ok(())
+		}
+
+		/// Freeze the multi-account `id`. While it is frozen nothing can be proposed or approved,
+		/// scheduled payments are skipped and its streams can't be claimed. Any single guardian
+		/// can freeze the account.
+		#[pallet::call_index(11)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn freeze(origin: OriginFor<T>, id: T::AccountId) -> DispatchResult {
+			let guardian = ensure_signed(origin)?;
+			ensure!(Self::is_guardian(&id, &guardian), Error::<T>::NotGuardian);
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			<Frozen<T>>::insert(&id, BoundedVec::default());
+			Self::deposit_event(Event::AccountFrozen { id, guardian });
+			Ok(())
+		}
+
+		/// Vote to unfreeze the multi-account `id`. It is unfrozen once the unfreeze threshold of
+		/// its guardians voted.
+		#[pallet::call_index(12)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn unfreeze(origin: OriginFor<T>, id: T::AccountId) -> DispatchResult {
+			let guardian = ensure_signed(origin)?;
+			let set = <Guardians<T>>::get(&id).ok_or(Error::<T>::NotGuardian)?;
+			ensure!(set.guardians.binary_search(&guardian).is_ok(), Error::<T>::NotGuardian);
+			let mut votes = <Frozen<T>>::get(&id).ok_or(Error::<T>::AccountNotFrozen)?;
+			let position =
+				votes.binary_search(&guardian).err().ok_or(Error::<T>::AlreadyVoted)?;
+			votes.try_insert(position, guardian.clone()).map_err(|_| Error::<T>::AlreadyVoted)?;
+			Self::deposit_event(Event::UnfreezeVote { id: id.clone(), guardian });
+			if votes.len() >= set.unfreeze_threshold as usize {
+				<Frozen<T>>::remove(&id);
+				Self::deposit_event(Event::AccountUnfrozen { id });
+			} else {
+				<Frozen<T>>::insert(&id, votes);
+			}
+			Ok(())
+		}
+
+		/// Remove the pending call `hash` of the multi-account `id` and return the proposer's
+		/// deposit. Any single guardian can veto a call, also while the account is frozen.
+		#[pallet::call_index(13)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn veto(origin: OriginFor<T>, id: T::AccountId, hash: CallHash) -> DispatchResult {
+			let guardian = ensure_signed(origin)?;
+			ensure!(Self::is_guardian(&id, &guardian), Error::<T>::NotGuardian);
+			let approvals = <Calls<T>>::get(&id, &hash);
+			ensure!(!approvals.is_empty(), Error::<T>::NotFound);
+			// commitments waiting for their reveal and deferred calls can still be vetoed
+			ensure!(
+				approvals.len() < <Threshold<T>>::get(&id) as usize ||
+					<Proposals<T>>::contains_key(&id, &hash),
+				Error::<T>::DispatchHasAlreadyOccured
+			);
+			<Calls<T>>::remove(&id, &hash);
+			<CallData<T>>::remove(&id, &hash);
+			<Commitments<T>>::remove(&id, &hash);
+			<ProposedAt<T>>::remove(&id, &hash);
+			Self::remove_proposal(&id, &hash);
+			Self::deposit_event(Event::ProposalVetoed { id, hash, guardian });
+			Ok(())
+		}
+
+		/// Let `heirs` take over the multi-account with `heir_threshold` if none of its
+		/// signatories proposes or approves a call for `inactivity_period` blocks. A claim only
+		/// succeeds if the signatories stay inactive for another `challenge_period` blocks after
+		/// it. An empty list of heirs removes the rule. Has to be dispatched by the multi-account
+		/// itself, i.e. through a call its signatories approved.
+		#[pallet::call_index(14)]
+		#[pallet::weight(Weight::from_parts(20_000, 0) + T::DbWeight::get().reads_writes(1, 2))]
+		pub fn set_inactivity_rule(
+			origin: OriginFor<T>,
+			inactivity_period: T::BlockNumber,
+			challenge_period: T::BlockNumber,
+			mut heirs: Vec<T::AccountId>,
+			heir_threshold: u16,
+		) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
+			heirs.sort();
+			heirs.dedup();
+			// a new rule starts without any claim made under the old one
+			<InheritanceClaims<T>>::remove(&id);
+			if heirs.is_empty() {
+				<InactivityRules<T>>::remove(&id);
+			} else {
+				ensure!(
+					!inactivity_period.is_zero() &&
+						!challenge_period.is_zero() &&
+						heir_threshold >= 1 && heir_threshold as usize <= heirs.len(),
+					Error::<T>::InvalidInactivityRule
+				);
+				ensure!(
+					heirs.iter().all(T::SignatoryValidator::is_valid),
+					Error::<T>::SignatoryNotVerified
+				);
+				let rule = InactivityRule {
+					inactivity_period,
+					challenge_period,
+					heirs: BoundedVec::try_from(heirs.clone())
+						.map_err(|_| Error::<T>::InvalidInactivityRule)?,
+					heir_threshold,
+				};
+				<InactivityRules<T>>::insert(&id, rule);
+			}
+			Self::deposit_event(Event::InactivityRuleSet { id, heirs, heir_threshold });
+			Ok(())
+		}
+
+		/// Claim the multi-account `id` for its heirs once it has been inactive for its
+		/// inactivity period. Starts the challenge period. Any heir can claim.
+		#[pallet::call_index(15)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+		pub fn claim_inheritance(origin: OriginFor<T>, id: T::AccountId) -> DispatchResult {
+			let heir = ensure_signed(origin)?;
+			let rule = <InactivityRules<T>>::get(&id).ok_or(Error::<T>::NotHeir)?;
+			ensure!(rule.heirs.binary_search(&heir).is_ok(), Error::<T>::NotHeir);
+			// guardians freeze an account to stop it from being taken over as well
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(!<InheritanceClaims<T>>::contains_key(&id), Error::<T>::ClaimAlreadyPending);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now >= <LastActivity<T>>::get(&id).saturating_add(rule.inactivity_period),
+				Error::<T>::AccountStillActive
+			);
+			let finalize_at = now.saturating_add(rule.challenge_period);
+			<InheritanceClaims<T>>::insert(
+				&id,
+				InheritanceClaim { claimant: heir.clone(), finalize_at },
+			);
+			Self::deposit_event(Event::InheritanceClaimed { id, heir, finalize_at });
+			Ok(())
+		}
+
+		/// Hand the multi-account `id` over to its heirs once the challenge period of the claim
+		/// is over: the heirs become the signatories, the heir threshold the threshold, and all
+		/// pending calls are removed. Any heir can finalize, as long as all heirs still pass the
+		/// `SignatoryValidator`.
+		#[pallet::call_index(16)]
+		#[pallet::weight({
+			let pending = T::MaxPendingPerAccount::get() as u64;
+			let signatories = T::MaxSignatories::get() as u64;
+			Weight::from_parts(50_000, 0) +
+				T::DbWeight::get().reads_writes(
+					2 * pending + 2 * signatories + 8,
+					7 * pending + 3 * signatories + 10,
+				)
+		})]
+		pub fn finalize_inheritance(origin: OriginFor<T>, id: T::AccountId) -> DispatchResult {
+			let heir = ensure_signed(origin)?;
+			let rule = <InactivityRules<T>>::get(&id).ok_or(Error::<T>::NotHeir)?;
+			ensure!(rule.heirs.binary_search(&heir).is_ok(), Error::<T>::NotHeir);
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			let claim = <InheritanceClaims<T>>::get(&id).ok_or(Error::<T>::NoPendingClaim)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now >= claim.finalize_at, Error::<T>::ChallengePeriodNotOver);
+			ensure!(
+				rule.heirs.iter().all(T::SignatoryValidator::is_valid),
+				Error::<T>::SignatoryNotVerified
+			);
+
+			let signatories = BoundedVec::try_from(rule.heirs.into_inner())
+				.map_err(|_| Error::<T>::TooManySignatories)?;
+			// approvals given by the old signatories don't count for the heirs
+			Self::clear_pending_calls(&id);
+			let _ = <Delegations<T>>::clear_prefix(&id, u32::MAX, None);
+			let _ = <Recoveries<T>>::clear_prefix(&id, u32::MAX, None);
+			<Account<T>>::insert(&id, &signatories);
+			<Threshold<T>>::insert(&id, rule.heir_threshold);
+			// the floor was chosen for the old threshold
+			<ThresholdDecays<T>>::remove(&id);
+			// and the guardians by the old signatories
+			<Guardians<T>>::remove(&id);
+			<Frozen<T>>::remove(&id);
+			<InactivityRules<T>>::remove(&id);
+			<InheritanceClaims<T>>::remove(&id);
+			<LastActivity<T>>::insert(&id, now);
+			Self::deposit_event(Event::InheritanceFinalized {
+				id,
+				signatories: signatories.into_inner(),
+				threshold: rule.heir_threshold,
+			});
+			Ok(())
+		}
+
+		/// Let `delegate` approve calls of the multi-account `id` in the sender's name up to and
+		/// including block `until`. An approval by the delegate counts as the sender's approval,
+		/// so the call can't be approved twice by both of them. A new delegation replaces the
+		/// previous one.
+		#[pallet::call_index(17)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn delegate_vote(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			delegate: T::AccountId,
+			until: T::BlockNumber,
+		) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			ensure!(Self::is_signatory(&id, &signer), Error::<T>::SignerIsNotApproved);
+			ensure!(
+				delegate != signer && until >= frame_system::Pallet::<T>::block_number(),
+				Error::<T>::InvalidDelegation
+			);
+			<Delegations<T>>::insert(&id, &signer, (delegate.clone(), until));
+			Self::deposit_event(Event::VoteDelegated { id, signer, delegate, until });
+			Ok(())
+		}
+
+		/// Take back the vote the sender delegated for the multi-account `id`.
+		#[pallet::call_index(18)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn revoke_delegation(origin: OriginFor<T>, id: T::AccountId) -> DispatchResult {
+			let signer = ensure_signed(origin)?;
+			let (delegate, _) =
+				<Delegations<T>>::take(&id, &signer).ok_or(Error::<T>::NoDelegation)?;
+			Self::deposit_event(Event::DelegationRevoked { id, signer, delegate });
+			Ok(())
+		}
+
+		/// Approve `call` of the multi-account `id` in the name of `signer`, who delegated its
+		/// vote to the sender. Dispatches the call if this approval reaches the threshold, and
+		/// only charges for the call if it was dispatched.
+		#[pallet::call_index(19)]
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			info.weight.saturating_add(
+				Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 5),
+			)
+		})]
+		pub fn approve_as_delegate(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			signer: T::AccountId,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			let delegate = ensure_signed(origin)?;
+			let (allowed, until) =
+				<Delegations<T>>::get(&id, &signer).ok_or(Error::<T>::NotDelegate)?;
+			ensure!(allowed == delegate, Error::<T>::NotDelegate);
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= until,
+				Error::<T>::DelegationExpired
+			);
+			let hash = call.using_encoded(sp_io::hashing::blake2_256);
+			Self::deposit_event(Event::DelegatedApproval {
+				id: id.clone(),
+				signer: signer.clone(),
+				delegate,
+				hash,
+			});
+			let base = Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 5);
+			let dispatched = Self::do_approve(id, signer, call)?;
+			Ok(Some(base.saturating_add(dispatched)).into())
+		}
+
+		/// Tell the signers what the pending call `hash` of `id` is about. Only the proposer can
+		/// set the metadata, and only until a second signatory approved the call. A deposit per
+		/// byte of metadata is reserved from the proposer and returned with the proposal
+		/// deposit.
+		#[pallet::call_index(20)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 1))]
+		pub fn set_proposal_metadata(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			hash: CallHash,
+			title: Vec<u8>,
+			description_hash: Option<[u8; 32]>,
+			tags: Vec<Vec<u8>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal = <Proposals<T>>::get(&id, &hash).ok_or(Error::<T>::NotFound)?;
+			ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+			ensure!(
+				<Calls<T>>::decode_len(&id, &hash).unwrap_or(0) < 2,
+				Error::<T>::MetadataLocked
+			);
+
+			let tags = tags
+				.into_iter()
+				.map(BoundedVec::try_from)
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|_| Error::<T>::MetadataTooLong)?;
+			let metadata = ProposalMetadata {
+				title: BoundedVec::try_from(title).map_err(|_| Error::<T>::MetadataTooLong)?,
+				description_hash,
+				tags: BoundedVec::try_from(tags).map_err(|_| Error::<T>::MetadataTooLong)?,
+			};
+			let deposit = T::MetadataDepositPerByte::get()
+				.saturating_mul((metadata.encoded_size() as u32).into());
+			let old_deposit =
+				<Metadata<T>>::get(&id, &hash).map_or(Zero::zero(), |(_, deposit)| deposit);
+			if deposit > old_deposit {
+				T::Currency::reserve(&who, deposit - old_deposit)?;
+			} else {
+				T::Currency::unreserve(&who, old_deposit - deposit);
+			}
+			<Metadata<T>>::insert(&id, &hash, (metadata.clone(), deposit));
+			Self::deposit_event(Event::MetadataSet { id, hash, metadata });
+			Ok(())
+		}
+
+		/// Propose a call without revealing it: `commitment` is the blake2-256 hash of the SCALE
+		/// encoded `(call, salt)`. The signatories approve the commitment with
+		/// `approve_commitment`, and once it has enough approvals any of them can reveal and
+		/// dispatch the call with `reveal_and_dispatch` up to and including block
+		/// `reveal_deadline`. The proposer's approval is counted and the proposal deposit is
+		/// reserved like for `account_create_call`.
+		#[pallet::call_index(21)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(5))]
+		pub fn account_create_commitment(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			commitment: CallHash,
+			reveal_deadline: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(Self::is_signatory(&id, &who), Error::<T>::SignerIsNotApproved);
+			ensure!(T::SignatoryValidator::is_valid(&who), Error::<T>::SignatoryNotVerified);
+			ensure!(
+				reveal_deadline > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::InvalidDeadline
+			);
+			ensure!(
+				<Calls<T>>::get(&id, &commitment).is_empty(),
+				Error::<T>::CallHashAlreadyExists
+			);
+
+			let approvals = BoundedVec::try_from(vec![who.clone()])
+				.map_err(|_| Error::<T>::TooManySignatories)?;
+			Self::add_pending(&id, &who)?;
+			let deposit = T::ProposalDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			let proposal = ProposalInfo {
+				proposer: who.clone(),
+				deposit,
+				spec_version: T::Version::get().spec_version,
+				transaction_version: T::Version::get().transaction_version,
+				stale: false,
+			};
+			<Proposals<T>>::insert(&id, &commitment, proposal);
+			<ProposedAt<T>>::insert(&id, &commitment, frame_system::Pallet::<T>::block_number());
+			<Calls<T>>::insert(&id, &commitment, approvals);
+			<Commitments<T>>::insert(&id, &commitment, reveal_deadline);
+			Self::deposit_event(Event::Proposal {
+				id: id.clone(),
+				proposer: who.clone(),
+				hash: commitment,
+			});
+			Self::note_activity(&id);
+			Self::deposit_event(Event::Approval {
+				id,
+				signer: who,
+				hash: commitment,
+				metadata: None,
+			});
+			Ok(())
+		}
+
+		/// Approve a pending commitment. Unlike `approve_or_dispatch_call` this never dispatches
+		/// anything, the call still has to be revealed.
+		#[pallet::call_index(22)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(4, 2))]
+		pub fn approve_commitment(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			commitment: CallHash,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(Self::is_signatory(&id, &who), Error::<T>::SignerIsNotApproved);
+			ensure!(T::SignatoryValidator::is_valid(&who), Error::<T>::SignatoryNotVerified);
+			let deadline =
+				<Commitments<T>>::get(&id, &commitment).ok_or(Error::<T>::CommitmentNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::CommitmentExpired
+			);
+			ensure!(!Self::is_stale(&id, &commitment), Error::<T>::ProposalIsStale);
+			let approvals_needed = Self::effective_threshold(&id, &commitment) as usize;
+			<Calls<T>>::try_mutate(&id, &commitment, |sig| -> DispatchResult {
+				ensure!(sig.len() < approvals_needed, Error::<T>::NoApprovalsNeeded);
+				let sorted_vec = Self::ensure_sorted_and_insert(sig.to_vec(), who.clone())?;
+				*sig =
+					BoundedVec::try_from(sorted_vec).map_err(|_| Error::<T>::TooManySignatories)?;
+				Ok(())
+			})?;
+			Self::note_activity(&id);
+			Self::deposit_event(Event::Approval {
+				id,
+				signer: who,
+				hash: commitment,
+				metadata: None,
+			});
+			Ok(())
+		}
+
+		/// Reveal the call behind a commitment that has enough approvals and dispatch it with the
+		/// multi-account as origin. Any signatory that knows the call and salt can reveal it. Like
+		/// other approved calls, a call that fails is recorded and can be revealed again until it
+		/// runs out of attempts.
+		#[pallet::call_index(23)]
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			info.weight.saturating_add(
+				Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(5, 4),
+			)
+		})]
+		pub fn reveal_and_dispatch(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			call: Box<<T as Config>::RuntimeCall>,
+			salt: [u8; 32],
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(Self::is_signatory(&id, &who), Error::<T>::SignerIsNotApproved);
+			ensure!(T::SignatoryValidator::is_valid(&who), Error::<T>::SignatoryNotVerified);
+			let commitment = (&call, salt).using_encoded(sp_io::hashing::blake2_256);
+			let deadline =
+				<Commitments<T>>::get(&id, &commitment).ok_or(Error::<T>::CommitmentNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::CommitmentExpired
+			);
+			ensure!(!Self::is_stale(&id, &commitment), Error::<T>::ProposalIsStale);
+			let signers = <Calls<T>>::get(&id, &commitment);
+			ensure!(
+				signers.len() >= Self::effective_threshold(&id, &commitment) as usize,
+				Error::<T>::NotEnoughApprovals
+			);
+			match Self::blocked_by(&id, &commitment) {
+				Some(Blocker::TooLate(_)) => return Err(Error::<T>::ProposalWindowClosed.into()),
+				Some(_) => return Err(Error::<T>::ConditionsNotMet.into()),
+				None => {},
+			}
+
+			let hash = call.using_encoded(sp_io::hashing::blake2_256);
+			Self::deposit_event(Event::CommitmentRevealed { id: id.clone(), commitment, hash });
+			// a failed call can be revealed again until it runs out of attempts
+			Self::dispatch_approved(id.clone(), &commitment, call, signers.into_inner());
+			if !<Proposals<T>>::contains_key(&id, &commitment) {
+				<Commitments<T>>::remove(&id, &commitment);
+			}
+			Ok(().into())
+		}
+
+		/// Remove a commitment whose reveal deadline has passed and return the proposer's
+		/// deposit. Anyone can clean up an expired commitment.
+		#[pallet::call_index(24)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 4))]
+		pub fn expire_commitment(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			commitment: CallHash,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let deadline =
+				<Commitments<T>>::get(&id, &commitment).ok_or(Error::<T>::CommitmentNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > deadline,
+				Error::<T>::CommitmentNotExpired
+			);
+			<Commitments<T>>::remove(&id, &commitment);
+			<Calls<T>>::remove(&id, &commitment);
+			<ProposedAt<T>>::remove(&id, &commitment);
+			Self::remove_proposal(&id, &commitment);
+			Self::deposit_event(Event::CommitmentExpired { id, commitment });
+			Ok(())
+		}
+
+		/// Only dispatch the pending call `hash` of `id` after the call `prerequisite` of the same
+		/// account was dispatched successfully, and within the blocks `not_before` to
+		/// `not_after`. If the call reaches its threshold before `prerequisite` ran or before
+		/// `not_before`, it waits for `execute_deferred`; after `not_after` the final approval
+		/// fails. Only the proposer can set the conditions, and only until a second signatory
+		/// approved the call.
+		#[pallet::call_index(25)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn set_proposal_conditions(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			hash: CallHash,
+			prerequisite: Option<CallHash>,
+			not_before: Option<T::BlockNumber>,
+			not_after: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal = <Proposals<T>>::get(&id, &hash).ok_or(Error::<T>::NotFound)?;
+			ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+			ensure!(
+				<Calls<T>>::decode_len(&id, &hash).unwrap_or(0) < 2,
+				Error::<T>::ConditionsLocked
+			);
+			ensure!(prerequisite != Some(hash), Error::<T>::InvalidConditions);
+			if let (Some(not_before), Some(not_after)) = (not_before, not_after) {
+				ensure!(not_before <= not_after, Error::<T>::InvalidConditions);
+			}
+			let conditions = ProposalConditions { prerequisite, not_before, not_after };
+			<Conditions<T>>::insert(&id, &hash, conditions.clone());
+			Self::deposit_event(Event::ConditionsSet { id, hash, conditions });
+			Ok(())
+		}
+
+		/// Dispatch a call that reached its threshold while its conditions did not hold, once
+		/// they do, or whose threshold decayed to the approvals it already has. Any signatory can
+		/// execute it.
+		#[pallet::call_index(26)]
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			info.weight.saturating_add(
+				Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(6, 5),
+			)
+		})]
+		pub fn execute_deferred(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(Self::is_signatory(&id, &who), Error::<T>::SignerIsNotApproved);
+			ensure!(T::SignatoryValidator::is_valid(&who), Error::<T>::SignatoryNotVerified);
+			let hash = call.using_encoded(sp_io::hashing::blake2_256);
+			ensure!(<Proposals<T>>::contains_key(&id, &hash), Error::<T>::NotFound);
+			ensure!(!Self::is_stale(&id, &hash), Error::<T>::ProposalIsStale);
+			let signers = <Calls<T>>::get(&id, &hash);
+			ensure!(
+				signers.len() >= Self::effective_threshold(&id, &hash) as usize,
+				Error::<T>::NotEnoughApprovals
+			);
+			match Self::blocked_by(&id, &hash) {
+				Some(Blocker::TooLate(_)) => Err(Error::<T>::ProposalWindowClosed.into()),
+				Some(_) => Err(Error::<T>::ConditionsNotMet.into()),
+				None => {
+					Self::dispatch_approved(id, &hash, call, signers.into_inner());
+					Ok(().into())
+				},
+			}
+		}
+
+		/// Dispatch a call again whose last execution failed, e.g. after the multi-account was
+		/// topped up. The same conditions as for `execute_deferred` apply, and a call is given up
+		/// after `MaxExecutionAttempts` attempts. Any signatory can retry.
+		#[pallet::call_index(29)]
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			info.weight.saturating_add(
+				Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(7, 5),
+			)
+		})]
+		pub fn retry_execution(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			let hash = call.using_encoded(sp_io::hashing::blake2_256);
+			ensure!(
+				<Executed<T>>::get(&id, &hash).map_or(false, |receipt| receipt.result.is_err()),
+				Error::<T>::NotRetryable
+			);
+			Self::execute_deferred(origin, id, call)
+		}
+
+		/// Approve several pending calls, possibly of different multi-accounts, by their hashes.
+		/// Every approval is applied on its own: calls that reach their threshold are dispatched,
+		/// and an approval that fails is rolled back without failing the others. The outcome of
+		/// each item is reported in a `BatchApproval` event. The calls dispatched by the batch
+		/// can use at most `max_weight` together.
+		///
+		/// Only calls whose preimage is kept on chain can be approved this way.
+		#[pallet::call_index(27)]
+		#[pallet::weight(
+			(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(7, 6))
+				.saturating_mul(items.len() as u64)
+				.saturating_add(*max_weight)
+		)]
+		pub fn approve_many(
+			origin: OriginFor<T>,
+			items: Vec<(T::AccountId, CallHash)>,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(items.len() as u32 <= T::MaxApprovalBatch::get(), Error::<T>::BatchTooLarge);
+			let base = (Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(7, 6))
+				.saturating_mul(items.len() as u64);
+			let mut used = Weight::zero();
+			for (index, (id, hash)) in items.into_iter().enumerate() {
+				let limit = max_weight.saturating_sub(used);
+				let result = with_transaction(|| {
+					let result = Self::approve_by_hash(&who, &id, hash, limit);
+					if result.is_ok() {
+						TransactionOutcome::Commit(result)
+					} else {
+						TransactionOutcome::Rollback(result)
+					}
+				});
+				if let Ok(weight) = result {
+					used = used.saturating_add(weight);
+				}
+				Self::deposit_event(Event::BatchApproval {
+					index: index as u32,
+					id,
+					hash,
+					result: result.map(|_| ()),
 				});
-				// remove call from storage because of space ??
-				// <Calls<T>>::remove(id.clone(), hash);
 			}
+			Ok(Some(base.saturating_add(used)).into())
+		}
 
+		/// Dispatch `call` from the sub-account `index` of the multi-account, a separate account
+		/// with its own balance that is controlled by the same signatories, e.g. for a payroll or
+		/// grants budget. The sub-account is derived like in `pallet_utility::as_derivative`, and
+		/// `index` has to be below `MaxSubAccounts`. Has to be dispatched by the multi-account
+		/// itself, i.e. through a call its signatories approved.
+		#[pallet::call_index(28)]
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			info.weight.saturating_add(
+				Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1),
+			)
+		})]
+		pub fn as_sub_account(
+			origin: OriginFor<T>,
+			index: u16,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			let id = ensure_signed(origin)?;
+			ensure!(<Threshold<T>>::contains_key(&id), Error::<T>::AccountNotFound);
+			ensure!(index < T::MaxSubAccounts::get(), Error::<T>::InvalidSubAccountIndex);
+			let sub_account = Self::sub_account_id(&id, index);
+			<SubAccounts<T>>::insert(&id, index, &sub_account);
+			call.dispatch(RawOrigin::Signed(sub_account.clone()).into())?;
+			Self::deposit_event(Event::SubAccountCall { id, index, sub_account });
 			Ok(().into())
 		}
+
+		/// Start replacing the signatory `lost` of the multi-account `id`, whose key was lost,
+		/// with `new`. The sender has to be another signatory and its vote counts for the
+		/// recovery. Once [`Pallet::recovery_threshold`] signatories voted and `RecoveryDelay`
+		/// blocks passed, anyone can finalize it. Until then `lost` can cancel it.
+		#[pallet::call_index(30)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 2))]
+		pub fn initiate_recovery(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			lost: T::AccountId,
+			new: T::AccountId,
+		) -> DispatchResult {
+			let initiator = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(Self::is_signatory(&id, &initiator), Error::<T>::SignerIsNotApproved);
+			ensure!(T::SignatoryValidator::is_valid(&initiator), Error::<T>::SignatoryNotVerified);
+			ensure!(
+				initiator != lost &&
+					Self::is_signatory(&id, &lost) &&
+					!Self::is_signatory(&id, &new),
+				Error::<T>::InvalidRecovery
+			);
+			ensure!(T::SignatoryValidator::is_valid(&new), Error::<T>::SignatoryNotVerified);
+			ensure!(!<Recoveries<T>>::contains_key(&id, &lost), Error::<T>::RecoveryAlreadyPending);
+			let delay_end =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::RecoveryDelay::get());
+			let votes = BoundedVec::try_from(vec![initiator.clone()])
+				.map_err(|_| Error::<T>::TooManySignatories)?;
+			<Recoveries<T>>::insert(&id, &lost, Recovery { new: new.clone(), votes, delay_end });
+			Self::note_activity(&id);
+			Self::deposit_event(Event::RecoveryInitiated { id, lost, new, initiator, delay_end });
+			Ok(())
+		}
+
+		/// Vote for the pending recovery of the signatory `lost` of the multi-account `id`.
+		#[pallet::call_index(31)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 2))]
+		pub fn vote_recovery(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			lost: T::AccountId,
+		) -> DispatchResult {
+			let voter = ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			ensure!(Self::is_signatory(&id, &voter), Error::<T>::SignerIsNotApproved);
+			ensure!(T::SignatoryValidator::is_valid(&voter), Error::<T>::SignatoryNotVerified);
+			ensure!(voter != lost, Error::<T>::InvalidRecovery);
+			<Recoveries<T>>::try_mutate(&id, &lost, |recovery| -> DispatchResult {
+				let recovery = recovery.as_mut().ok_or(Error::<T>::NoRecovery)?;
+				let position =
+					recovery.votes.binary_search(&voter).err().ok_or(Error::<T>::AlreadyVoted)?;
+				recovery
+					.votes
+					.try_insert(position, voter.clone())
+					.map_err(|_| Error::<T>::TooManySignatories)?;
+				Ok(())
+			})?;
+			Self::note_activity(&id);
+			Self::deposit_event(Event::RecoveryVote { id, lost, voter });
+			Ok(())
+		}
+
+		/// Cancel the recovery of the sender's seat in the multi-account `id`, proving that its
+		/// key was not lost.
+		#[pallet::call_index(32)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn cancel_recovery(origin: OriginFor<T>, id: T::AccountId) -> DispatchResult {
+			let lost = ensure_signed(origin)?;
+			<Recoveries<T>>::take(&id, &lost).ok_or(Error::<T>::NoRecovery)?;
+			Self::deposit_event(Event::RecoveryCancelled { id, lost });
+			Ok(())
+		}
+
+		/// Replace the signatory `lost` of the multi-account `id` with the new key of its
+		/// recovery, once enough signatories voted and the delay is over. The new key takes over
+		/// the approvals of pending calls, while calls proposed by the lost key keep their
+		/// proposer and deposit. The approvals of dispatched calls are left as they are. Anyone
+		/// can finalize, unless the account is frozen.
+		#[pallet::call_index(33)]
+		#[pallet::weight({
+			let pending = T::MaxPendingPerAccount::get() as u64;
+			let signatories = T::MaxSignatories::get() as u64;
+			Weight::from_parts(50_000, 0) +
+				T::DbWeight::get()
+					.reads_writes(2 * pending + signatories + 5, pending + signatories + 4)
+		})]
+		pub fn finalize_recovery(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			lost: T::AccountId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			let recovery = <Recoveries<T>>::get(&id, &lost).ok_or(Error::<T>::NoRecovery)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= recovery.delay_end,
+				Error::<T>::RecoveryDelayNotOver
+			);
+			ensure!(
+				recovery.votes.len() >= Self::recovery_threshold(&id) as usize,
+				Error::<T>::NotEnoughRecoveryVotes
+			);
+			let new = recovery.new;
+			// the new key may have become a signatory through another recovery meanwhile
+			ensure!(!Self::is_signatory(&id, &new), Error::<T>::InvalidRecovery);
+			ensure!(T::SignatoryValidator::is_valid(&new), Error::<T>::SignatoryNotVerified);
+
+			<Account<T>>::try_mutate(&id, |signatories| -> DispatchResult {
+				signatories.retain(|signatory| signatory != &lost);
+				let position = signatories.binary_search(&new).unwrap_or_else(|p| p);
+				signatories
+					.try_insert(position, new.clone())
+					.map_err(|_| Error::<T>::TooManySignatories.into())
+			})?;
+			// only pending calls, of which there are at most `MaxPendingPerAccount`. dispatched
+			// calls keep their approvals as a record
+			let approved: Vec<_> = <Proposals<T>>::iter_key_prefix(&id)
+				.map(|hash| (hash, <Calls<T>>::get(&id, hash)))
+				.filter(|(_, approvals)| approvals.contains(&lost))
+				.collect();
+			for (hash, mut approvals) in approved {
+				approvals.retain(|approval| approval != &lost);
+				let position = approvals.binary_search(&new).unwrap_or_else(|p| p);
+				let _ = approvals.try_insert(position, new.clone());
+				<Calls<T>>::insert(&id, hash, approvals);
+			}
+			// votes of the lost key don't count for other recoveries
+			let voted: Vec<_> = <Recoveries<T>>::iter_prefix(&id)
+				.filter(|(_, other)| other.votes.contains(&lost))
+				.collect();
+			for (other, mut recovery) in voted {
+				recovery.votes.retain(|voter| voter != &lost);
+				<Recoveries<T>>::insert(&id, other, recovery);
+			}
+			<Recoveries<T>>::remove(&id, &lost);
+			<Delegations<T>>::remove(&id, &lost);
+			Self::deposit_event(Event::SignatoryRecovered { id, lost, new });
+			Ok(())
+		}
+
+		/// Let calls that are stuck without enough approvals, e.g. because signatories stopped
+		/// responding, pass with fewer approvals over time. A call needs one approval less once
+		/// it has been pending for `decay_after` blocks, and another one less every `decay_step`
+		/// blocks after that, down to `floor`. Once the approvals a call already has meet its
+		/// lowered threshold, any signatory can dispatch it with `execute_deferred`. `None` turns
+		/// the decay off. Has to be dispatched by the multi-account itself, i.e. through a call
+		/// its signatories approved.
+		#[pallet::call_index(34)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_threshold_decay(
+			origin: OriginFor<T>,
+			decay: Option<ThresholdDecay<T::BlockNumber>>,
+		) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			let threshold = <Threshold<T>>::get(&id);
+			ensure!(threshold > 0, Error::<T>::AccountNotFound);
+			match &decay {
+				Some(decay) => {
+					ensure!(
+						!decay.decay_step.is_zero() && decay.floor >= 1 && decay.floor <= threshold,
+						Error::<T>::InvalidThresholdDecay
+					);
+					<ThresholdDecays<T>>::insert(&id, decay);
+				},
+				None => <ThresholdDecays<T>>::remove(&id),
+			}
+			Self::deposit_event(Event::ThresholdDecaySet { id, decay });
+			Ok(())
+		}
+
+		/// Remove the pending call `hash` of `id` after the block window its proposer set for it
+		/// closed, or once it has been pending for longer than `ProposalLifetime`, and return the
+		/// deposits. Anyone can clean up such a call, whether it is still waiting for approvals or
+		/// was deferred.
+		#[pallet::call_index(35)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 9))]
+		pub fn expire_proposal(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			hash: CallHash,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(<Proposals<T>>::contains_key(&id, &hash), Error::<T>::NotFound);
+			let now = frame_system::Pallet::<T>::block_number();
+			let outlived = <ProposedAt<T>>::get(&id, &hash).map_or(false, |proposed_at| {
+				now > proposed_at.saturating_add(T::ProposalLifetime::get())
+			});
+			ensure!(
+				outlived || matches!(Self::blocked_by(&id, &hash), Some(Blocker::TooLate(_))),
+				Error::<T>::ProposalNotExpired
+			);
+			<Calls<T>>::remove(&id, &hash);
+			<CallData<T>>::remove(&id, &hash);
+			<Commitments<T>>::remove(&id, &hash);
+			<ProposedAt<T>>::remove(&id, &hash);
+			Self::remove_proposal(&id, &hash);
+			Self::deposit_event(Event::ProposalExpired { id, hash });
+			Ok(())
+		}
+
+		/// Remove the receipt of the dispatched call `hash` of `id` together with its approvals,
+		/// and return the receipt deposit to the multi-account. Anyone can remove a receipt
+		/// `ReceiptLifetime` blocks after the last attempt, unless the call can still be retried
+		/// or a pending call of the account waits for it as its prerequisite.
+		#[pallet::call_index(36)]
+		#[pallet::weight(
+			Weight::from_parts(10_000, 0) +
+				T::DbWeight::get()
+					.reads_writes(T::MaxPendingPerAccount::get() as u64 + 3, 5)
+		)]
+		pub fn remove_receipt(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			hash: CallHash,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let receipt = <Executed<T>>::get(&id, &hash).ok_or(Error::<T>::NotFound)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now > receipt.at.saturating_add(T::ReceiptLifetime::get()),
+				Error::<T>::ReceiptStillNeeded
+			);
+			// there is a condition for each pending call at most
+			ensure!(
+				!<Proposals<T>>::contains_key(&id, &hash) &&
+					<Conditions<T>>::iter_prefix_values(&id)
+						.all(|conditions| conditions.prerequisite != Some(hash)),
+				Error::<T>::ReceiptStillNeeded
+			);
+			<Executed<T>>::remove(&id, &hash);
+			<Calls<T>>::remove(&id, &hash);
+			<ProposedAt<T>>::remove(&id, &hash);
+			T::Currency::unreserve(&id, receipt.deposit);
+			<ReceiptDeposits<T>>::mutate(&id, |total| {
+				*total = total.saturating_sub(receipt.deposit)
+			});
+			Self::deposit_event(Event::ReceiptRemoved { id, hash });
+			Ok(())
+		}
+
+		/// Withdraw the pending call `hash` of `id`, together with the approvals it collected so
+		/// far, and get the deposits back. Only the signatory that proposed the call can cancel
+		/// it.
+		#[pallet::call_index(37)]
+		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 9))]
+		pub fn cancel_proposal(
+			origin: OriginFor<T>,
+			id: T::AccountId,
+			hash: CallHash,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal = <Proposals<T>>::get(&id, &hash).ok_or(Error::<T>::NotFound)?;
+			ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+			<Calls<T>>::remove(&id, &hash);
+			<CallData<T>>::remove(&id, &hash);
+			<Commitments<T>>::remove(&id, &hash);
+			<ProposedAt<T>>::remove(&id, &hash);
+			Self::remove_proposal(&id, &hash);
+			Self::deposit_event(Event::ProposalCancelled { id, hash });
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -243,6 +2135,562 @@ pub mod pallet {
 				.expect("infinite length input; no invalid inputs for type; qed")
 		}
 
+		/// The sub-account `index` of the multi-account `id`. This is the same account
+		/// `pallet_utility::as_derivative` would dispatch from.
+		pub fn sub_account_id(id: &T::AccountId, index: u16) -> T::AccountId {
+			let entropy = (b"modlpy/utilisuba", id, index).using_encoded(sp_io::hashing::blake2_256);
+			Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+				.expect("infinite length input; no invalid inputs for type; qed")
+		}
+
+		/// Sort `signatories`, check that together with `threshold` they describe a valid
+		/// multi-account and derive its ID with [`Pallet::multi_account_id`].
+		///
+		/// Unlike `multi_account_id` the input does not have to be sorted, which makes this the
+		/// safe entry point for the runtime API and the node CLI.
+		pub fn derive_account_id(
+			mut signatories: Vec<T::AccountId>,
+			threshold: u16,
+		) -> Result<T::AccountId, Error<T>> {
+			ensure!(threshold >= 1, Error::<T>::MinimumThreshold);
+			ensure!(
+				!signatories.is_empty() && threshold as usize <= signatories.len(),
+				Error::<T>::TooFewSignatories
+			);
+			ensure!(
+				signatories.len() <= T::MaxSignatories::get() as usize,
+				Error::<T>::TooManySignatories
+			);
+			signatories.sort();
+			ensure!(
+				signatories.windows(2).all(|pair| pair[0] != pair[1]),
+				Error::<T>::DuplicateSignatories
+			);
+			Ok(Self::multi_account_id(&signatories, threshold))
+		}
+
+		/// Dispatch `call` with the multi-account `id` as origin inside a storage transaction that
+		/// is always rolled back, so nothing it does is persisted.
+		///
+		/// Returns the dispatch result together with the events the call emitted. This is what the
+		/// `dry_run` runtime API uses to show signers what the final approval would do.
+		pub fn dry_run(
+			id: T::AccountId,
+			call: <T as Config>::RuntimeCall,
+		) -> (DispatchResultWithPostInfo, Vec<<T as frame_system::Config>::RuntimeEvent>) {
+			with_transaction(|| {
+				let events_before = frame_system::Pallet::<T>::event_count() as usize;
+				let result = call.dispatch(RawOrigin::Signed(id).into());
+				let events = frame_system::Pallet::<T>::read_events_no_consensus()
+					.skip(events_before)
+					.map(|record| record.event)
+					.collect::<Vec<_>>();
+				TransactionOutcome::Rollback(Ok::<_, DispatchError>((result, events)))
+			})
+			.unwrap_or_else(|err| (Err(err.into()), Vec::new()))
+		}
+
+		/// The number of calls of `id` that are still waiting for approvals, as counted against
+		/// `MaxPendingPerAccount`.
+		pub fn pending_call_count(id: &T::AccountId) -> u32 {
+			<PendingProposals<T>>::get(id)
+		}
+
+		/// Put a scheduled transfer on the agenda of block `at`, or of the first of the following
+		/// blocks that still has room. Returns the block it ended up in.
+		fn enqueue(at: T::BlockNumber, key: (T::AccountId, u32)) -> Option<T::BlockNumber> {
+			// how many blocks after `at` are tried before giving up
+			const MAX_DELAY: u32 = 16;
+			let mut block = at;
+			for _ in 0..=MAX_DELAY {
+				let queued = <Agenda<T>>::try_mutate(block, |agenda| agenda.try_push(key.clone()));
+				if queued.is_ok() {
+					return Some(block)
+				}
+				block = block.saturating_add(One::one());
+			}
+			None
+		}
+
+		/// Make one payment of a scheduled transfer and queue the next one.
+		fn pay_scheduled(now: T::BlockNumber, id: T::AccountId, index: u32) -> Weight {
+			let mut schedule = match <Schedules<T>>::get(&id, index) {
+				Some(schedule) => schedule,
+				None => return T::DbWeight::get().reads(1),
+			};
+			let weight = Weight::from_parts(50_000, 0) + T::DbWeight::get().reads_writes(4, 4);
+			// a frozen account misses the payment like one without enough funds
+			let result = if Self::is_frozen(&id) {
+				Err(Error::<T>::AccountFrozen.into())
+			} else {
+				T::Currency::transfer(
+					&id,
+					&schedule.dest,
+					schedule.amount,
+					ExistenceRequirement::KeepAlive,
+				)
+			};
+			if result.is_ok() {
+				schedule.remaining = schedule.remaining.map(|remaining| remaining.saturating_sub(1));
+			}
+			Self::deposit_event(Event::ScheduledTransferExecuted { id: id.clone(), index, result });
+
+			if schedule.remaining == Some(0) {
+				<Schedules<T>>::remove(&id, index);
+				Self::release_schedule(&id, &schedule);
+				Self::deposit_event(Event::ScheduleCompleted { id, index });
+				return weight
+			}
+			match Self::enqueue(now.saturating_add(schedule.interval), (id.clone(), index)) {
+				Some(next) => {
+					schedule.next = next;
+					<Schedules<T>>::insert(&id, index, schedule);
+				},
+				None => {
+					<Schedules<T>>::remove(&id, index);
+					Self::release_schedule(&id, &schedule);
+					Self::deposit_event(Event::ScheduleCancelled { id, index });
+				},
+			}
+			weight
+		}
+
+		/// Return the deposit of a schedule that was removed and free its slot.
+		fn release_schedule(id: &T::AccountId, schedule: &ScheduledTransferOf<T>) {
+			T::Currency::unreserve(id, schedule.deposit);
+			<ScheduleCount<T>>::mutate(id, |count| *count = count.saturating_sub(1));
+		}
+
+		/// Convert a number of blocks to a balance, so it can be multiplied with a stream's rate.
+		fn blocks_to_balance(blocks: T::BlockNumber) -> BalanceOf<T> {
+			blocks.saturated_into::<u128>().saturated_into()
+		}
+
+		/// How much `stream` released up to block `now`, including what was claimed already.
+		pub fn released(stream: &StreamOf<T>, now: T::BlockNumber) -> BalanceOf<T> {
+			let elapsed = now.min(stream.end).saturating_sub(stream.start);
+			stream.rate.saturating_mul(Self::blocks_to_balance(elapsed))
+		}
+
+		/// Add the approval of signatory `who` to `call` and dispatch it once the threshold is
+		/// reached. Returns the actual weight of the call if it was dispatched, zero otherwise.
+		fn do_approve(
+			id: T::AccountId,
+			who: T::AccountId,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> Result<Weight, DispatchError> {
+			ensure!(!Self::is_frozen(&id), Error::<T>::AccountFrozen);
+			let signatories = <Account<T>>::get(&id);
+			ensure!(
+				signatories.into_inner().binary_search(&who).is_ok(),
+				Error::<T>::SignerIsNotApproved
+			);
+			ensure!(T::SignatoryValidator::is_valid(&who), Error::<T>::SignatoryNotVerified);
+			let mut signers: Vec<_> = Vec::new();
+			let mut number_of_approvals = 0;
+			let hash = &call.using_encoded(sp_io::hashing::blake2_256);
+			// the approvals alone can't tell once the threshold changed, e.g. after a decayed
+			// threshold was turned off again
+			ensure!(!Self::succeeded(&id, hash), Error::<T>::DispatchHasAlreadyOccured);
+			let approvals_needed = Self::effective_threshold(&id, hash);
+			ensure!(!Self::is_stale(&id, hash), Error::<T>::ProposalIsStale);
+			<Calls<T>>::try_mutate(&id, hash, |sig| -> DispatchResult {
+				// if the number of approvals needed has passed and the call has been dispatched
+				// there is no need to add 32 bytes in storage that is of no use
+				// so we return early here
+				if sig.as_slice().len() as u16 >= approvals_needed {
+					return Err(Error::<T>::DispatchHasAlreadyOccured.into());
+				};
+				// the ensure_sorted_and_insert already makes a check to confirm if an account id
+				// already exists in the bounded vec. so we can be sure that a double vote will not
+				// occur.
+				number_of_approvals = sig.as_slice().len() as u16;
+				let sorted_vec =
+					Self::ensure_sorted_and_insert(sig.as_slice().to_vec(), who.clone())?;
+				signers.extend(sorted_vec.clone());
+				*sig =
+					BoundedVec::try_from(sorted_vec).map_err(|_| Error::<T>::TooManySignatories)?;
+				Ok(())
+			})?;
+
+			Self::note_activity(&id);
+			Self::remove_expired_delegation(&id, &who);
+			Self::deposit_event(Event::Approval {
+				id: id.clone(),
+				signer: who.clone(),
+				hash: hash.clone(),
+				metadata: <Metadata<T>>::get(&id, hash).map(|(metadata, _)| metadata),
+			});
+
+			if (number_of_approvals + 1) == approvals_needed {
+				match Self::blocked_by(&id, hash) {
+					Some(Blocker::TooLate(_)) =>
+						return Err(Error::<T>::ProposalWindowClosed.into()),
+					// the approvals are kept, `execute_deferred` dispatches the call later
+					Some(reason) => {
+						Self::deposit_event(Event::DispatchDeferred {
+							id: id.clone(),
+							hash: hash.clone(),
+							reason,
+						});
+						return Ok(Weight::zero())
+					},
+					None => return Ok(Self::dispatch_approved(id, hash, call, signers)),
+				}
+			}
+
+			Ok(Weight::zero())
+		}
+
+		/// Approve the call `hash` of `id` from its preimage on chain. The weight of the call has
+		/// to fit in `limit` if this approval completes it. Returns the weight the call actually
+		/// used, zero if it was not dispatched, e.g. because it was deferred.
+		fn approve_by_hash(
+			who: &T::AccountId,
+			id: &T::AccountId,
+			hash: CallHash,
+			limit: Weight,
+		) -> Result<Weight, DispatchError> {
+			let data = <CallData<T>>::get(id, &hash).ok_or(Error::<T>::PreimageNotFound)?;
+			let call = <T as Config>::RuntimeCall::decode(&mut &data[..])
+				.map_err(|_| Error::<T>::PreimageNotFound)?;
+			let completes = <Calls<T>>::decode_len(id, &hash).unwrap_or(0) + 1 >=
+				Self::effective_threshold(id, &hash) as usize;
+			let weight =
+				if completes { call.get_dispatch_info().weight } else { Weight::zero() };
+			ensure!(weight.all_lte(limit), Error::<T>::BatchWeightExhausted);
+			Self::do_approve(id.clone(), who.clone(), Box::new(call))
+		}
+
+		/// Dispatch a call that reached its threshold, record the outcome and return the weight
+		/// the call actually used. The proposal is forgotten once the call succeeded or ran out of
+		/// attempts, and a call that ran out of attempts loses its approvals too. A failed call
+		/// does not fail the approval that dispatched it.
+		fn dispatch_approved(
+			id: T::AccountId,
+			hash: &CallHash,
+			call: Box<<T as Config>::RuntimeCall>,
+			signers: Vec<T::AccountId>,
+		) -> Weight {
+			let info = call.get_dispatch_info();
+			let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
+			let weight = extract_actual_weight(&result, &info);
+			let result = result.map(|_| ()).map_err(|err| err.error);
+			// a call that closed the account leaves nothing to keep a receipt for
+			let attempt = if <Threshold<T>>::contains_key(&id) {
+				Self::record_execution(&id, hash, result)
+			} else {
+				1
+			};
+			Self::deposit_event(Event::CallExecuted { id: id.clone(), hash: *hash, attempt, result });
+			if result.is_ok() || attempt >= T::MaxExecutionAttempts::get() {
+				<CallData<T>>::remove(&id, hash);
+				<ProposedAt<T>>::remove(&id, hash);
+				Self::remove_proposal(&id, hash);
+			}
+			if result.is_ok() {
+				Self::deposit_event(Event::Call { id, signatories: signers, hash: *hash });
+			} else if attempt >= T::MaxExecutionAttempts::get() {
+				// the call was given up. unlike after a success, its approvals go too, so that it
+				// can be proposed again
+				<Calls<T>>::remove(&id, hash);
+			}
+			weight
+		}
+
+		/// Record an attempt to dispatch the call `hash` of `id` and return its number. The first
+		/// attempt reserves the receipt deposit from the multi-account, if it can pay it. A call
+		/// that was given up and proposed again starts counting from one, with the same deposit.
+		fn record_execution(id: &T::AccountId, hash: &CallHash, result: DispatchResult) -> u32 {
+			let at = frame_system::Pallet::<T>::block_number();
+			let receipt = match <Executed<T>>::get(id, hash) {
+				Some(receipt)
+					if receipt.result.is_err() &&
+						receipt.attempts >= T::MaxExecutionAttempts::get() =>
+					ExecutionReceipt { at, attempts: 1, result, ..receipt },
+				Some(receipt) =>
+					ExecutionReceipt { at, attempts: receipt.attempts + 1, result, ..receipt },
+				None => {
+					let deposit = T::ReceiptDeposit::get();
+					let deposit = if T::Currency::reserve(id, deposit).is_ok() {
+						deposit
+					} else {
+						Zero::zero()
+					};
+					<ReceiptDeposits<T>>::mutate(id, |total| {
+						*total = total.saturating_add(deposit)
+					});
+					ExecutionReceipt { at, attempts: 1, result, deposit }
+				},
+			};
+			let attempt = receipt.attempts;
+			<Executed<T>>::insert(id, hash, receipt);
+			attempt
+		}
+
+		/// Whether the call `hash` of `id` was dispatched successfully.
+		pub fn succeeded(id: &T::AccountId, hash: &CallHash) -> bool {
+			<Executed<T>>::get(id, hash).map_or(false, |receipt| receipt.result.is_ok())
+		}
+
+		/// Why the call `hash` of `id` can't be dispatched right now, `None` if nothing stops it
+		/// once it has enough approvals.
+		pub fn blocked_by(id: &T::AccountId, hash: &CallHash) -> Option<Blocker<T::BlockNumber>> {
+			let conditions = <Conditions<T>>::get(id, hash)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			if let Some(not_after) = conditions.not_after.filter(|not_after| now > *not_after) {
+				return Some(Blocker::TooLate(not_after))
+			}
+			if let Some(prerequisite) = conditions
+				.prerequisite
+				.filter(|prerequisite| !Self::succeeded(id, prerequisite))
+			{
+				return Some(Blocker::PrerequisiteNotExecuted(prerequisite))
+			}
+			conditions.not_before.filter(|not_before| now < *not_before).map(Blocker::TooEarly)
+		}
+
+		/// Whether `delegate` can approve calls of `id` in the name of `signer` right now.
+		pub fn is_delegate(
+			id: &T::AccountId,
+			signer: &T::AccountId,
+			delegate: &T::AccountId,
+		) -> bool {
+			<Delegations<T>>::get(id, signer).map_or(false, |(allowed, until)| {
+				allowed == *delegate && frame_system::Pallet::<T>::block_number() <= until
+			})
+		}
+
+		/// Remove the delegation of `signer` for `id` if it expired. Expired delegations are
+		/// cleaned up whenever the signer proposes or approves a call itself.
+		fn remove_expired_delegation(id: &T::AccountId, signer: &T::AccountId) {
+			let now = frame_system::Pallet::<T>::block_number();
+			<Delegations<T>>::mutate_exists(id, signer, |delegation| {
+				if matches!(delegation, Some((_, until)) if *until < now) {
+					*delegation = None;
+				}
+			});
+		}
+
+		/// Remember that a signatory of `id` proposed or approved a call, which cancels any
+		/// pending inheritance claim.
+		fn note_activity(id: &T::AccountId) {
+			<LastActivity<T>>::insert(id, frame_system::Pallet::<T>::block_number());
+			if <InheritanceClaims<T>>::take(id).is_some() {
+				Self::deposit_event(Event::InheritanceClaimCancelled { id: id.clone() });
+			}
+		}
+
+		/// Remove all pending calls of `id` and return their proposers' deposits.
+		fn clear_pending_calls(id: &T::AccountId) {
+			// everything a pending call keeps is stored under its hash, so the work is bounded by
+			// `MaxPendingPerAccount`. the approvals of dispatched calls stay with their receipts
+			for (hash, proposal) in <Proposals<T>>::drain_prefix(id) {
+				T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+				if let Some((_, deposit)) = <Metadata<T>>::take(id, hash) {
+					T::Currency::unreserve(&proposal.proposer, deposit);
+				}
+				<Calls<T>>::remove(id, hash);
+				<CallData<T>>::remove(id, hash);
+				<Commitments<T>>::remove(id, hash);
+				<Conditions<T>>::remove(id, hash);
+				<ProposedAt<T>>::remove(id, hash);
+				Self::deposit_event(Event::ProposalRemoved { id: id.clone(), hash });
+			}
+			<PendingProposals<T>>::remove(id);
+			let _ = <PendingBySignatory<T>>::clear_prefix(id, u32::MAX, None);
+		}
+
+		/// Count a new proposal of `proposer` for `id`, unless it would go over one of the
+		/// limits.
+		fn add_pending(id: &T::AccountId, proposer: &T::AccountId) -> DispatchResult {
+			<PendingProposals<T>>::try_mutate(id, |pending| -> DispatchResult {
+				ensure!(
+					*pending < T::MaxPendingPerAccount::get(),
+					Error::<T>::TooManyPendingForAccount
+				);
+				*pending += 1;
+				Ok(())
+			})?;
+			<PendingBySignatory<T>>::try_mutate(id, proposer, |pending| -> DispatchResult {
+				ensure!(
+					*pending < T::MaxPendingPerSignatory::get(),
+					Error::<T>::TooManyPendingForSignatory
+				);
+				*pending += 1;
+				Ok(())
+			})
+		}
+
+		/// Forget the proposal of a call and return its deposit to the proposer.
+		pub(crate) fn remove_proposal(id: &T::AccountId, hash: &CallHash) {
+			if let Some(proposal) = <Proposals<T>>::take(id, hash) {
+				let decrement = |pending: &mut Option<u32>| {
+					*pending = pending.and_then(|count| count.checked_sub(1)).filter(|c| *c > 0)
+				};
+				<PendingProposals<T>>::mutate_exists(id, decrement);
+				<PendingBySignatory<T>>::mutate_exists(id, &proposal.proposer, decrement);
+				T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+				if let Some((_, deposit)) = <Metadata<T>>::take(id, hash) {
+					T::Currency::unreserve(&proposal.proposer, deposit);
+				}
+			}
+			<Conditions<T>>::remove(id, hash);
+		}
+
+		/// Count the proposals made before the pending limits existed into `PendingProposals`
+		/// and `PendingBySignatory`, and start their `ProposalLifetime` now if they have no
+		/// `ProposedAt` yet. Runs once, when the on-chain storage version is below 1.
+		pub(crate) fn migrate_pending_counters() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+			let _ = <PendingProposals<T>>::clear(u32::MAX, None);
+			let _ = <PendingBySignatory<T>>::clear(u32::MAX, None);
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut proposals = 0u64;
+			for (id, hash, proposal) in <Proposals<T>>::iter() {
+				proposals += 1;
+				<PendingProposals<T>>::mutate(&id, |pending| *pending = pending.saturating_add(1));
+				<PendingBySignatory<T>>::mutate(&id, &proposal.proposer, |pending| {
+					*pending = pending.saturating_add(1)
+				});
+				if !<ProposedAt<T>>::contains_key(&id, &hash) {
+					<ProposedAt<T>>::insert(&id, &hash, now);
+				}
+			}
+			STORAGE_VERSION.put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(4 * proposals + 1, 3 * proposals + 3)
+		}
+
+		/// Mark the proposals made under another transaction version stale and return their
+		/// deposits, until `limit` is used up. The sweep continues where it stopped the next time.
+		pub(crate) fn sweep_stale_proposals(limit: Weight) -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			let cursor = match <StaleSweep<T>>::get() {
+				Some(cursor) => cursor,
+				None => return weight,
+			};
+			weight.saturating_accrue(T::DbWeight::get().writes(1));
+			// reading the proposal and its metadata, and updating them and the proposer's reserve
+			let per_proposal =
+				Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(3, 3);
+			let transaction_version = T::Version::get().transaction_version;
+			let mut proposals = if cursor.is_empty() {
+				<Proposals<T>>::iter()
+			} else {
+				<Proposals<T>>::iter_from(cursor.into_inner())
+			};
+			while weight.saturating_add(per_proposal).all_lte(limit) {
+				let (id, hash, mut proposal) = match proposals.next() {
+					Some(entry) => entry,
+					None => {
+						<StaleSweep<T>>::kill();
+						return weight
+					},
+				};
+				weight.saturating_accrue(per_proposal);
+				if !proposal.stale && proposal.transaction_version != transaction_version {
+					T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+					if let Some((_, deposit)) = <Metadata<T>>::take(&id, hash) {
+						T::Currency::unreserve(&proposal.proposer, deposit);
+					}
+					proposal.deposit = Zero::zero();
+					proposal.stale = true;
+					<Proposals<T>>::insert(&id, hash, proposal);
+					Self::deposit_event(Event::ProposalStale { id, hash });
+				}
+			}
+			<StaleSweep<T>>::put(BoundedVec::truncate_from(proposals.last_raw_key().to_vec()));
+			weight
+		}
+
+		/// Whether the call `hash` of `id` was proposed under a different transaction version than
+		/// the current one.
+		pub fn is_stale(id: &T::AccountId, hash: &CallHash) -> bool {
+			<Proposals<T>>::get(id, hash).map_or(false, |proposal| {
+				proposal.stale ||
+					proposal.transaction_version != T::Version::get().transaction_version
+			})
+		}
+
+		/// Whether a guardian froze the multi-account `id`.
+		pub fn is_frozen(id: &T::AccountId) -> bool {
+			<Frozen<T>>::contains_key(id)
+		}
+
+		/// Whether `who` is one of the guardians of the multi-account `id`.
+		pub fn is_guardian(id: &T::AccountId, who: &T::AccountId) -> bool {
+			<Guardians<T>>::get(id).map_or(false, |set| set.guardians.binary_search(who).is_ok())
+		}
+
+		/// The approvals the pending call `hash` of `id` needs now: the threshold, lowered by its
+		/// account's threshold decay the longer the call is pending, but never below the floor.
+		pub fn effective_threshold(id: &T::AccountId, hash: &CallHash) -> u16 {
+			let threshold = <Threshold<T>>::get(id);
+			let (decay, proposed_at) =
+				match (<ThresholdDecays<T>>::get(id), <ProposedAt<T>>::get(id, hash)) {
+					(Some(decay), Some(proposed_at)) => (decay, proposed_at),
+					_ => return threshold,
+				};
+			let pending = frame_system::Pallet::<T>::block_number().saturating_sub(proposed_at);
+			if pending < decay.decay_after || decay.decay_step.is_zero() {
+				return threshold
+			}
+			let steps = ((pending - decay.decay_after) / decay.decay_step)
+				.saturated_into::<u16>()
+				.saturating_add(1);
+			threshold.saturating_sub(steps).max(decay.floor.min(threshold))
+		}
+
+		/// Votes needed to replace a lost signatory key of `id`: the threshold of the account, but
+		/// no more than the other signatories.
+		pub fn recovery_threshold(id: &T::AccountId) -> u16 {
+			let others = <Account<T>>::decode_len(id).unwrap_or(0).saturating_sub(1);
+			<Threshold<T>>::get(id).min(others as u16).max(1)
+		}
+
+		/// Whether `who` is one of the signatories of the multi-account `id`.
+		pub fn is_signatory(id: &T::AccountId, who: &T::AccountId) -> bool {
+			<Account<T>>::get(id).binary_search(who).is_ok()
+		}
+
+		/// Take the estimated `fee` of a transaction the multi-account `id` pays for out of its
+		/// fee budget.
+		pub(crate) fn take_fee_budget(id: &T::AccountId, fee: BalanceOf<T>) {
+			<FeeBudget<T>>::mutate(id, |budget| *budget = budget.saturating_sub(fee));
+		}
+
+		/// Settle the fee the multi-account `id` paid for a transaction of `who` once it was
+		/// dispatched. What was taken from the budget beyond the `actual` fee is returned to it.
+		/// If the call failed, `who` pays the actual fee back to the multi-account, and only when
+		/// it can't the fee stays charged to the budget.
+		pub(crate) fn settle_fee(
+			id: &T::AccountId,
+			who: &T::AccountId,
+			estimate: BalanceOf<T>,
+			actual: BalanceOf<T>,
+			succeeded: bool,
+		) {
+			let paid = if !succeeded &&
+				T::Currency::transfer(who, id, actual, ExistenceRequirement::KeepAlive).is_ok()
+			{
+				Zero::zero()
+			} else {
+				actual
+			};
+			<FeeBudget<T>>::mutate(id, |budget| {
+				*budget = budget.saturating_add(estimate.saturating_sub(paid))
+			});
+			if !paid.is_zero() {
+				Self::deposit_event(Event::FeePaid {
+					id: id.clone(),
+					signer: who.clone(),
+					amount: paid,
+				});
+			}
+		}
+
 		/// Check that signatories is sorted and doesn't contain sender, then insert sender.
 		fn ensure_sorted_and_insert(
 			other_signatories: Vec<T::AccountId>,
@@ -288,5 +2736,136 @@ pub mod pallet {
 		DispatchHasAlreadyOccured,
 		/// Call hash has already been created by a user
 		CallHashAlreadyExists,
+		/// The same account was given more than once as a signatory.
+		DuplicateSignatories,
+		/// The origin is not a registered multi-account.
+		AccountNotFound,
+		/// A multi-account is already registered under this id.
+		AccountAlreadyExists,
+		/// The multi-account has locked funds, so it can't be closed.
+		AccountHasLocks,
+		/// The call was proposed under a different transaction version and has to be proposed
+		/// again.
+		ProposalIsStale,
+		/// A scheduled transfer needs a non-zero interval and count.
+		InvalidSchedule,
+		/// No block near the first payment has room for another scheduled transfer.
+		AgendaFull,
+		/// The multi-account has no scheduled transfer with this index.
+		ScheduleNotFound,
+		/// The multi-account still has scheduled transfers, so it can't be closed.
+		HasPendingSchedules,
+		/// A stream needs a non-zero rate and duration.
+		InvalidStream,
+		/// The multi-account has no stream with this index.
+		StreamNotFound,
+		/// Only the beneficiary of a stream can claim from it.
+		NotStreamBeneficiary,
+		/// The stream has not released anything since the last claim.
+		NothingToClaim,
+		/// The multi-account still has streams, so it can't be closed.
+		HasActiveStreams,
+		/// A guardian froze the multi-account, so nothing can be proposed, approved or paid out.
+		AccountFrozen,
+		/// The multi-account is not frozen.
+		AccountNotFrozen,
+		/// The sender is not a guardian of the multi-account.
+		NotGuardian,
+		/// The guardian already voted to unfreeze the multi-account.
+		AlreadyVoted,
+		/// The unfreeze threshold has to be between one and the number of guardians.
+		InvalidGuardians,
+		/// The periods of an inactivity rule have to be non-zero and the heir threshold between
+		/// one and the number of heirs.
+		InvalidInactivityRule,
+		/// The sender is not an heir of the multi-account.
+		NotHeir,
+		/// A signatory proposed or approved a call within the inactivity period.
+		AccountStillActive,
+		/// An heir already claimed the multi-account.
+		ClaimAlreadyPending,
+		/// No heir claimed the multi-account.
+		NoPendingClaim,
+		/// The challenge period of the claim is not over yet.
+		ChallengePeriodNotOver,
+		/// A signatory can't delegate to itself or until a block that has already passed.
+		InvalidDelegation,
+		/// The signatory didn't delegate its vote to the sender.
+		NotDelegate,
+		/// The delegation has expired.
+		DelegationExpired,
+		/// The signatory has no delegation to revoke.
+		NoDelegation,
+		/// Only the proposer of a call can set its metadata.
+		NotProposer,
+		/// The metadata can't be changed once a second signatory approved the call.
+		MetadataLocked,
+		/// The title, a tag or the number of tags is over its limit.
+		MetadataTooLong,
+		/// The multi-account has no pending commitment with this hash.
+		CommitmentNotFound,
+		/// The reveal deadline has to be in the future.
+		InvalidDeadline,
+		/// The reveal deadline of the commitment has passed.
+		CommitmentExpired,
+		/// The reveal deadline of the commitment has not passed yet.
+		CommitmentNotExpired,
+		/// The commitment doesn't have enough approvals to be revealed yet.
+		NotEnoughApprovals,
+		/// The conditions can't be changed once a second signatory approved the call.
+		ConditionsLocked,
+		/// `not_before` is after `not_after`, or the call depends on itself.
+		InvalidConditions,
+		/// The last block the call could be dispatched in has passed.
+		ProposalWindowClosed,
+		/// The prerequisite or the earliest block of the call has not been reached yet.
+		ConditionsNotMet,
+		/// The multi-account has `MaxPendingPerAccount` pending proposals already.
+		TooManyPendingForAccount,
+		/// The signatory has `MaxPendingPerSignatory` pending proposals for the account already.
+		TooManyPendingForSignatory,
+		/// An `approve_many` batch has more than `MaxApprovalBatch` items.
+		BatchTooLarge,
+		/// The call is too large to be kept on chain, so it can't be approved by its hash.
+		PreimageNotFound,
+		/// Dispatching the call would go over the `max_weight` of the batch.
+		BatchWeightExhausted,
+		/// A sub-account of the multi-account still holds funds, so it can't be closed.
+		SubAccountHasFunds,
+		/// Only a call whose last execution failed can be retried.
+		NotRetryable,
+		/// The signatory is not accepted by the `SignatoryValidator`, e.g. it is not a verified
+		/// user.
+		SignatoryNotVerified,
+		/// The lost key already has a pending recovery.
+		RecoveryAlreadyPending,
+		/// There is no pending recovery for the key.
+		NoRecovery,
+		/// The lost key is not a signatory, the new key already is one, or a signatory tried to
+		/// recover its own key.
+		InvalidRecovery,
+		/// The lost key can still cancel the recovery.
+		RecoveryDelayNotOver,
+		/// Fewer signatories than the recovery threshold voted for the recovery.
+		NotEnoughRecoveryVotes,
+		/// The decay step is zero, or the floor is zero or above the threshold.
+		InvalidThresholdDecay,
+		/// The multi-account has `MaxSchedulesPerAccount` scheduled transfers already.
+		TooManySchedules,
+		/// All schedule indices have been used.
+		ScheduleIndexOverflow,
+		/// The call has not been pending for `ProposalLifetime` blocks yet, and it has no block
+		/// window or its window has not closed yet.
+		ProposalNotExpired,
+		/// The receipt is too recent, the call can still be retried, or a pending call waits for
+		/// it as its prerequisite.
+		ReceiptStillNeeded,
+		/// The sub-account index is not below `MaxSubAccounts`.
+		InvalidSubAccountIndex,
+		/// The multi-account still has receipts of dispatched calls, so it can't be closed. They
+		/// can be removed with `remove_receipt`.
+		HasReceipts,
+		/// All stream indices have been used.
+		StreamIndexOverflow,
 	}
 }
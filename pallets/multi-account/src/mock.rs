@@ -1,17 +1,48 @@
 pub use crate as multi_account;
-use frame_support::{parameter_types, sp_runtime::BuildStorage, traits::Everything};
+use frame_support::{
+	parameter_types,
+	sp_runtime::BuildStorage,
+	traits::{ConstU8, Everything, Get},
+	weights::IdentityFee,
+};
 use frame_system::mocking::MockBlock;
 use pallet_balances;
-use sp_runtime::traits::ConstU32;
+use sp_runtime::traits::{ConstU16, ConstU32, ConstU64};
 type Block = MockBlock<Test>;
 
 parameter_types! {
 	pub const MaxSignatories:u32 = 100;
 	pub const ExistentialDeposit: u64 = 1;
+	pub static RegistrationDeposit: u64 = 0;
+	pub static ProposalDeposit: u64 = 0;
+	pub static PreimageDepositPerByte: u64 = 0;
+	pub static ScheduleDeposit: u64 = 0;
+	pub static ReceiptDeposit: u64 = 0;
+	pub static TransactionVersion: u32 = 1;
+	pub static Unverified: Vec<u64> = vec![];
 
 
 }
 
+/// The runtime version, with a `transaction_version` tests can change to simulate an upgrade.
+pub struct Version;
+impl Get<sp_version::RuntimeVersion> for Version {
+	fn get() -> sp_version::RuntimeVersion {
+		sp_version::RuntimeVersion {
+			transaction_version: TransactionVersion::get(),
+			..Default::default()
+		}
+	}
+}
+
+/// Accepts every signatory except the ones tests put in `Unverified`.
+pub struct Verified;
+impl multi_account::SignatoryValidator<u64> for Verified {
+	fn is_valid(who: &u64) -> bool {
+		!Unverified::get().contains(who)
+	}
+}
+
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 
 frame_support::construct_runtime!(
@@ -22,6 +53,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system,
 		Balances: pallet_balances,
+		TransactionPayment: pallet_transaction_payment,
 		MultiAccount: multi_account,
 	}
 );
@@ -41,7 +73,7 @@ impl frame_system::Config for Test {
 	type Header = sp_runtime::generic::Header<Self::BlockNumber, Self::Hashing>;
 	type BlockHashCount = ();
 	type DbWeight = ();
-	type Version = ();
+	type Version = Version;
 	type PalletInfo = PalletInfo;
 	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
@@ -69,12 +101,44 @@ impl pallet_balances::Config for Test {
 	type MaxHolds = ();
 }
 
+impl pallet_transaction_payment::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+	type OperationalFeeMultiplier = ConstU8<5>;
+	type WeightToFee = IdentityFee<u64>;
+	type LengthToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
 impl multi_account::Config for Test {
 	type WeightInfo = ();
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	//type MaxSignatories = frame_support::traits::ConstU32<100>;
 	type MaxSignatories = MaxSignatories;
+	type MaxCallSize = ConstU32<1024>;
+	type Currency = Balances;
+	type ApprovalPriorityBoost = ConstU64<1_000>;
+	type MaxScheduledPerBlock = ConstU32<2>;
+	type ScheduleDeposit = ScheduleDeposit;
+	type MaxSchedulesPerAccount = ConstU32<3>;
+	type RegistrationDeposit = RegistrationDeposit;
+	type ProposalDeposit = ProposalDeposit;
+	type PreimageDepositPerByte = PreimageDepositPerByte;
+	type MaxTitleLength = ConstU32<32>;
+	type MaxTags = ConstU32<2>;
+	type MaxTagLength = ConstU32<8>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type MaxPendingPerAccount = ConstU32<4>;
+	type MaxPendingPerSignatory = ConstU32<3>;
+	type ProposalLifetime = ConstU64<20>;
+	type MaxApprovalBatch = ConstU32<3>;
+	type MaxSubAccounts = ConstU16<4>;
+	type MaxExecutionAttempts = ConstU32<2>;
+	type ReceiptDeposit = ReceiptDeposit;
+	type ReceiptLifetime = ConstU64<10>;
+	type RecoveryDelay = ConstU64<5>;
+	type SignatoryValidator = Verified;
 }
 
 // Build genesis storage according to the mock runtime.
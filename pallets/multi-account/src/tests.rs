@@ -1,5 +1,6 @@
 use crate::{mock::*, *};
-use frame_support::{assert_noop, assert_ok};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, weights::Weight};
 use sp_runtime::traits::Zero;
 //use frame_support::traits::fungible::Mutate;
 use frame_support::traits::fungible::Inspect;
@@ -10,6 +11,19 @@ const CHARLIE: u64 = 3000;
 const OSCAR: u64 = 10000;
 const FREE_BALANCE: u64 = 10_000_000;
 
+/// Register the multi-account of ALICE, BOB and CHARLIE with `threshold`, as ALICE.
+fn registered(threshold: u16) -> u64 {
+	let other_signatories = vec![BOB, CHARLIE];
+	let id = MultiAccount::multi_account_id(other_signatories.as_slice(), threshold);
+	assert_ok!(MultiAccount::register_account(
+		RuntimeOrigin::signed(ALICE),
+		id,
+		other_signatories,
+		threshold,
+	));
+	id
+}
+
 #[test]
 fn it_should_create_an_account_in_database() {
 	new_test_ext().execute_with(|| {
@@ -153,3 +167,2013 @@ fn should_be_able_to_transfer_to_multi_sig_account() {
 		assert_eq!(bob_balance, transfer_amount);
 	})
 }
+
+#[test]
+fn dry_run_should_not_persist_the_dispatched_call() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+
+		let call = RuntimeCall::Balances(pallet_balances::Call::<Test>::transfer_keep_alive {
+			dest: OSCAR,
+			value: 50000,
+		});
+		let (result, events) = MultiAccount::dry_run(id, call);
+		assert_ok!(result);
+		// the transfer emitted its events inside the rolled back transaction
+		assert!(events.iter().any(|event| matches!(
+			event,
+			RuntimeEvent::Balances(pallet_balances::Event::Transfer { to: OSCAR, .. })
+		)));
+		// but nothing it did was kept
+		assert_eq!(Balances::balance(&id), FREE_BALANCE);
+		assert_eq!(Balances::balance(&OSCAR), 0);
+	})
+}
+
+#[test]
+fn derive_account_id_should_sort_and_validate_signatories() {
+	new_test_ext().execute_with(|| {
+		let threshold = 2;
+		// the signatories can be given in any order
+		assert_eq!(
+			MultiAccount::derive_account_id(vec![OSCAR, ALICE, BOB], threshold).ok(),
+			Some(MultiAccount::multi_account_id(&[ALICE, BOB, OSCAR], threshold))
+		);
+		assert!(matches!(
+			MultiAccount::derive_account_id(vec![ALICE, BOB, ALICE], threshold),
+			Err(crate::Error::<Test>::DuplicateSignatories)
+		));
+		assert!(matches!(
+			MultiAccount::derive_account_id(vec![ALICE], threshold),
+			Err(crate::Error::<Test>::TooFewSignatories)
+		));
+		assert!(matches!(
+			MultiAccount::derive_account_id(vec![ALICE, BOB], 0),
+			Err(crate::Error::<Test>::MinimumThreshold)
+		));
+	})
+}
+
+#[test]
+fn creating_a_call_should_emit_a_proposal_and_the_proposers_approval() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let call = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = call.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(call)
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::Proposal { id, proposer: ALICE, hash }.into(),
+		);
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::Approval { id, signer: ALICE, hash, metadata: None }.into(),
+		);
+	})
+}
+
+#[test]
+fn call_data_should_be_kept_until_the_call_is_dispatched() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let call = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = call.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(call.clone())
+		));
+		assert_eq!(
+			MultiAccount::get_call_data(id, hash).map(|data| data.into_inner()),
+			Some(call.encode())
+		);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(call)
+		));
+		assert_eq!(MultiAccount::get_call_data(id, hash), None);
+	})
+}
+
+#[test]
+fn dispatched_calls_should_not_count_as_pending() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let first = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let second = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![2] });
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(first.clone())
+		));
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(second)
+		));
+		assert_eq!(MultiAccount::pending_call_count(&id), 2);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(first)
+		));
+		assert_eq!(MultiAccount::pending_call_count(&id), 1);
+	})
+}
+
+fn approval(id: u64) -> RuntimeCall {
+	RuntimeCall::MultiAccount(crate::Call::approve_or_dispatch_call {
+		id,
+		call: Box::new(RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] })),
+	})
+}
+
+/// Run an approval of `signer` through `SponsorApprovalFees` as if its dispatch had `result`, and
+/// return how much the signer and the multi-account paid for it.
+fn sponsored_approval(
+	signer: u64,
+	id: u64,
+	result: frame_support::dispatch::DispatchResult,
+) -> (u64, u64) {
+	use frame_support::dispatch::{GetDispatchInfo, PostDispatchInfo};
+	use sp_runtime::traits::SignedExtension;
+
+	let call = approval(id);
+	let info = call.get_dispatch_info();
+	let before = (Balances::balance(&signer), Balances::balance(&id));
+	let pre = SponsorApprovalFees::<Test>::from(0)
+		.pre_dispatch(&signer, &call, &info, 100)
+		.expect("The fee can be paid");
+	SponsorApprovalFees::<Test>::post_dispatch(
+		Some(pre),
+		&info,
+		&PostDispatchInfo::default(),
+		100,
+		&result,
+	)
+	.expect("The fee can be corrected");
+	(before.0 - Balances::balance(&signer), before.1 - Balances::balance(&id))
+}
+
+#[test]
+fn approval_fees_should_be_paid_by_the_account_within_the_budget() {
+	use frame_support::dispatch::GetDispatchInfo;
+	use sp_runtime::DispatchError;
+
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let fee = TransactionPayment::compute_fee(100, &approval(id).get_dispatch_info(), 0);
+		for who in [id, BOB, CHARLIE, OSCAR] {
+			Balances::force_set_balance(RuntimeOrigin::root(), who, 10 * fee)
+				.expect("Balance should have been set successfully");
+		}
+
+		// signers pay for themselves before the account sets a budget
+		assert_eq!(sponsored_approval(BOB, id, Ok(())), (fee, 0));
+
+		// only the multi-account itself can set its budget
+		assert_noop!(
+			MultiAccount::set_fee_budget(RuntimeOrigin::signed(BOB), 100),
+			crate::Error::<Test>::AccountNotFound
+		);
+		assert_ok!(MultiAccount::set_fee_budget(RuntimeOrigin::signed(id), 2 * fee));
+		// accounts that aren't signatories, or that don't pass the validator, pay for themselves
+		assert_eq!(sponsored_approval(OSCAR, id, Ok(())), (fee, 0));
+		Unverified::set(vec![CHARLIE]);
+		assert_eq!(sponsored_approval(CHARLIE, id, Ok(())), (fee, 0));
+		Unverified::set(vec![]);
+
+		// the account pays for its signatories, so ALICE doesn't need any funds
+		assert_eq!(Balances::balance(&ALICE), 0);
+		assert_eq!(sponsored_approval(ALICE, id, Ok(())), (0, fee));
+		assert_eq!(MultiAccount::fee_budget(id), fee);
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::FeePaid { id, signer: ALICE, amount: fee }.into(),
+		);
+
+		// the signer of a call that fails pays for it after all
+		assert_eq!(sponsored_approval(BOB, id, Err(DispatchError::BadOrigin)), (fee, 0));
+		assert_eq!(MultiAccount::fee_budget(id), fee);
+		// unless it can't, then the fee stays charged to the budget
+		assert_eq!(sponsored_approval(ALICE, id, Err(DispatchError::BadOrigin)), (0, fee));
+		assert_eq!(MultiAccount::fee_budget(id), 0);
+
+		// once the budget is used up signers pay for themselves again
+		assert_eq!(sponsored_approval(CHARLIE, id, Ok(())), (fee, 0));
+	})
+}
+
+#[test]
+fn invalid_approvals_should_be_rejected_by_the_transaction_pool() {
+	use frame_support::dispatch::GetDispatchInfo;
+	use sp_runtime::{
+		traits::SignedExtension,
+		transaction_validity::{InvalidTransaction, TransactionValidity},
+	};
+
+	fn validate(signer: u64, call: RuntimeCall) -> TransactionValidity {
+		let info = call.get_dispatch_info();
+		CheckMultiAccountApproval::<Test>::new().validate(&signer, &call, &info, 100)
+	}
+
+	new_test_ext().execute_with(|| {
+		let id = registered(3);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let propose = RuntimeCall::MultiAccount(crate::Call::account_create_call {
+			id,
+			call: Box::new(remark.clone()),
+		});
+		let approve = RuntimeCall::MultiAccount(crate::Call::approve_or_dispatch_call {
+			id,
+			call: Box::new(remark.clone()),
+		});
+
+		assert_eq!(
+			validate(2, propose.clone()),
+			Err(InvalidTransaction::Custom(extensions::NOT_A_SIGNATORY).into())
+		);
+		// approvals of a call that is not on chain wait for its proposal
+		let proposal = validate(ALICE, propose.clone()).expect("ALICE is a signatory");
+		let early = validate(BOB, approve.clone()).expect("BOB is a signatory");
+		assert_eq!(early.requires.len(), 1);
+		assert!(proposal.provides.contains(&early.requires[0]));
+
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+		assert_eq!(
+			validate(BOB, propose),
+			Err(InvalidTransaction::Custom(extensions::ALREADY_PROPOSED).into())
+		);
+		assert_eq!(
+			validate(ALICE, approve.clone()),
+			Err(InvalidTransaction::Custom(extensions::ALREADY_APPROVED).into())
+		);
+		Unverified::set(vec![BOB]);
+		assert_eq!(
+			validate(BOB, approve.clone()),
+			Err(InvalidTransaction::Custom(extensions::NOT_VERIFIED).into())
+		);
+		Unverified::set(vec![]);
+		let second = validate(BOB, approve.clone()).expect("BOB has not approved yet");
+		assert!(second.requires.is_empty());
+		assert_eq!(second.priority, 0);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark.clone())
+		));
+		// the last approval dispatches the call, so it is boosted
+		let last = validate(CHARLIE, approve.clone()).expect("CHARLIE has not approved yet");
+		assert_eq!(last.priority, 1_000);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark)
+		));
+		assert_eq!(validate(BOB, approve), Err(InvalidTransaction::Stale.into()));
+	})
+}
+
+#[test]
+fn closing_an_account_should_sweep_its_funds_and_return_all_deposits() {
+	new_test_ext().execute_with(|| {
+		RegistrationDeposit::set(10);
+		ProposalDeposit::set(5);
+		for who in [ALICE, BOB] {
+			Balances::force_set_balance(RuntimeOrigin::root(), who, 1_000)
+				.expect("Balance should have been set successfully");
+		}
+		let id = registered(2);
+		assert_noop!(
+			MultiAccount::register_account(RuntimeOrigin::signed(ALICE), id, vec![BOB, CHARLIE], 2),
+			crate::Error::<Test>::AccountAlreadyExists
+		);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+
+		// a proposal that is still pending when the account is closed
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark)
+		));
+		let close = Box::new(RuntimeCall::MultiAccount(crate::Call::close_account { dest: OSCAR }));
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			close.clone()
+		));
+		assert_eq!(Balances::reserved_balance(&ALICE), 15);
+		assert_eq!(Balances::reserved_balance(&BOB), 5);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(RuntimeOrigin::signed(BOB), id, close));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::AccountClosed { id, dest: OSCAR, amount: FREE_BALANCE }.into(),
+		);
+		assert_eq!(Balances::balance(&OSCAR), FREE_BALANCE);
+		assert_eq!(Balances::balance(&id), 0);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert_eq!(Balances::reserved_balance(&BOB), 0);
+		assert!(MultiAccount::get_account(id).is_empty());
+		assert_eq!(MultiAccount::get_threshold(id), 0);
+		assert_eq!(MultiAccount::pending_call_count(&id), 0);
+		// no receipt is kept for the call that closed the account
+		assert!(crate::Executed::<Test>::iter_prefix(id).next().is_none());
+	})
+}
+
+#[test]
+fn an_account_should_only_be_closed_once_its_receipts_are_removed() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark)
+		));
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR),
+			crate::Error::<Test>::HasReceipts
+		);
+
+		run_to_block(12);
+		assert_ok!(MultiAccount::remove_receipt(RuntimeOrigin::signed(OSCAR), id, hash));
+		assert_ok!(MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR));
+	})
+}
+
+#[test]
+fn an_account_with_locked_funds_should_not_be_closed() {
+	use frame_support::traits::{LockableCurrency, WithdrawReasons};
+
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		Balances::set_lock(*b"testlock", &id, 100, WithdrawReasons::all());
+
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR),
+			crate::Error::<Test>::AccountHasLocks
+		);
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(BOB), OSCAR),
+			crate::Error::<Test>::AccountNotFound
+		);
+	})
+}
+
+#[test]
+fn proposals_should_become_stale_when_the_transaction_version_changes() {
+	use frame_support::traits::{Get, Hooks};
+
+	new_test_ext().execute_with(|| {
+		ProposalDeposit::set(5);
+		for who in [ALICE, BOB] {
+			Balances::force_set_balance(RuntimeOrigin::root(), who, 1_000)
+				.expect("Balance should have been set successfully");
+		}
+		let id = registered(2);
+		let call = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = call.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(call.clone())
+		));
+		let proposal = MultiAccount::proposal(id, hash).expect("The call was just proposed");
+		assert_eq!(proposal.spec_version, Version::get().spec_version);
+		assert_eq!(proposal.transaction_version, 1);
+		assert!(!proposal.stale);
+		assert_ok!(MultiAccount::set_proposal_metadata(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			hash,
+			b"remark".to_vec(),
+			None,
+			vec![]
+		));
+		let (_, metadata_deposit) =
+			MultiAccount::proposal_metadata(id, hash).expect("metadata should have been set");
+
+		// an upgrade that keeps the transaction version leaves the proposal alone
+		MultiAccount::on_runtime_upgrade();
+		assert!(!MultiAccount::is_stale(&id, &hash));
+
+		TransactionVersion::set(2);
+		MultiAccount::on_runtime_upgrade();
+		// the proposal can't be approved right away, but its deposit is only returned once the
+		// sweep has the weight to reach it
+		assert!(MultiAccount::is_stale(&id, &hash));
+		MultiAccount::on_idle(1, Weight::zero());
+		assert_eq!(Balances::reserved_balance(&ALICE), 5 + metadata_deposit);
+		MultiAccount::on_idle(1, Weight::MAX);
+		assert!(crate::StaleSweep::<Test>::get().is_none());
+		frame_system::Pallet::<Test>::assert_last_event(Event::ProposalStale { id, hash }.into());
+		// the metadata deposit is returned with the proposal deposit
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert_eq!(MultiAccount::proposal_metadata(id, hash), None);
+		assert_noop!(
+			MultiAccount::approve_or_dispatch_call(
+				RuntimeOrigin::signed(BOB),
+				id,
+				Box::new(call.clone())
+			),
+			crate::Error::<Test>::ProposalIsStale
+		);
+
+		// the call can be proposed again under the new version
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(call.clone())
+		));
+		assert_eq!(MultiAccount::get_call(id, hash).into_inner(), vec![BOB]);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(call)
+		));
+	})
+}
+
+fn run_to_block(n: u64) {
+	use frame_support::traits::Hooks;
+
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		MultiAccount::on_initialize(next);
+	}
+}
+
+#[test]
+fn scheduled_transfers_should_be_paid_every_interval_until_done() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+
+		assert_noop!(
+			MultiAccount::schedule_transfer(RuntimeOrigin::signed(id), OSCAR, 100, 0, None),
+			crate::Error::<Test>::InvalidSchedule
+		);
+		// two payments, five blocks apart, starting at block 6
+		assert_ok!(MultiAccount::schedule_transfer(
+			RuntimeOrigin::signed(id),
+			OSCAR,
+			100,
+			5,
+			Some(2)
+		));
+		// one that goes on until it is cancelled
+		assert_ok!(MultiAccount::schedule_transfer(
+			RuntimeOrigin::signed(id),
+			CHARLIE,
+			10,
+			5,
+			None
+		));
+		// the agenda of block 6 is full, so the third schedule starts a block later
+		assert_ok!(MultiAccount::schedule_transfer(
+			RuntimeOrigin::signed(id),
+			BOB,
+			1,
+			5,
+			Some(1)
+		));
+		assert_eq!(MultiAccount::schedule(id, 2).map(|schedule| schedule.next), Some(7));
+
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR),
+			crate::Error::<Test>::HasPendingSchedules
+		);
+
+		run_to_block(5);
+		assert_eq!(Balances::balance(&OSCAR), 0);
+		run_to_block(6);
+		assert_eq!(Balances::balance(&OSCAR), 100);
+		assert_eq!(Balances::balance(&CHARLIE), 10);
+		run_to_block(11);
+		assert_eq!(Balances::balance(&OSCAR), 200);
+		assert_eq!(Balances::balance(&CHARLIE), 20);
+		assert_eq!(Balances::balance(&BOB), 1);
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::ScheduleCompleted { id, index: 0 }.into(),
+		);
+		assert_eq!(MultiAccount::schedule(id, 0), None);
+
+		assert_ok!(MultiAccount::cancel_schedule(RuntimeOrigin::signed(id), 1));
+		assert_noop!(
+			MultiAccount::cancel_schedule(RuntimeOrigin::signed(id), 1),
+			crate::Error::<Test>::ScheduleNotFound
+		);
+		run_to_block(30);
+		assert_eq!(Balances::balance(&OSCAR), 200);
+		assert_eq!(Balances::balance(&CHARLIE), 20);
+	})
+}
+
+#[test]
+fn scheduled_transfers_should_be_paid_for_and_limited_per_account() {
+	new_test_ext().execute_with(|| {
+		ScheduleDeposit::set(5);
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+
+		for interval in 1..=3 {
+			assert_ok!(MultiAccount::schedule_transfer(
+				RuntimeOrigin::signed(id),
+				OSCAR,
+				1,
+				interval,
+				None
+			));
+		}
+		assert_eq!(Balances::reserved_balance(&id), 15);
+		assert_noop!(
+			MultiAccount::schedule_transfer(RuntimeOrigin::signed(id), OSCAR, 1, 4, None),
+			crate::Error::<Test>::TooManySchedules
+		);
+
+		// cancelling a schedule returns its deposit and frees its slot
+		assert_ok!(MultiAccount::cancel_schedule(RuntimeOrigin::signed(id), 0));
+		assert_eq!(Balances::reserved_balance(&id), 10);
+		assert_eq!(MultiAccount::schedule_count(id), 2);
+
+		// indices are never reused
+		crate::NextScheduleIndex::<Test>::put(u32::MAX);
+		assert_noop!(
+			MultiAccount::schedule_transfer(RuntimeOrigin::signed(id), OSCAR, 1, 4, None),
+			crate::Error::<Test>::ScheduleIndexOverflow
+		);
+	})
+}
+
+#[test]
+fn streams_should_release_funds_per_block_and_refund_the_rest_on_cancel() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+
+		// 10 per block for 10 blocks
+		assert_ok!(MultiAccount::create_stream(RuntimeOrigin::signed(id), OSCAR, 10, 10));
+		assert_eq!(Balances::reserved_balance(&id), 100);
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR),
+			crate::Error::<Test>::HasActiveStreams
+		);
+
+		System::set_block_number(4);
+		assert_noop!(
+			MultiAccount::claim_stream(RuntimeOrigin::signed(BOB), id, 0),
+			crate::Error::<Test>::NotStreamBeneficiary
+		);
+		assert_ok!(MultiAccount::claim_stream(RuntimeOrigin::signed(OSCAR), id, 0));
+		assert_eq!(Balances::balance(&OSCAR), 30);
+		assert_noop!(
+			MultiAccount::claim_stream(RuntimeOrigin::signed(OSCAR), id, 0),
+			crate::Error::<Test>::NothingToClaim
+		);
+
+		System::set_block_number(6);
+		assert_ok!(MultiAccount::cancel_stream(RuntimeOrigin::signed(id), 0));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::StreamCancelled { id, index: 0, paid: 20, refunded: 50 }.into(),
+		);
+		assert_eq!(Balances::balance(&OSCAR), 50);
+		assert_eq!(Balances::reserved_balance(&id), 0);
+		assert_eq!(Balances::free_balance(&id), FREE_BALANCE - 50);
+		assert_eq!(MultiAccount::stream(id, 0), None);
+	})
+}
+
+#[test]
+fn a_finished_stream_should_be_removed_after_the_last_claim() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		assert_ok!(MultiAccount::create_stream(RuntimeOrigin::signed(id), OSCAR, 10, 10));
+
+		System::set_block_number(100);
+		assert_ok!(MultiAccount::claim_stream(RuntimeOrigin::signed(OSCAR), id, 0));
+		assert_eq!(Balances::balance(&OSCAR), 100);
+		assert_eq!(MultiAccount::stream(id, 0), None);
+		assert_eq!(Balances::reserved_balance(&id), 0);
+
+		// indices are never reused
+		crate::NextStreamIndex::<Test>::put(u32::MAX);
+		assert_noop!(
+			MultiAccount::create_stream(RuntimeOrigin::signed(id), OSCAR, 10, 10),
+			crate::Error::<Test>::StreamIndexOverflow
+		);
+	})
+}
+
+#[test]
+fn a_guardian_should_freeze_an_account_until_enough_guardians_unfreeze_it() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		assert_noop!(
+			MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![OSCAR], 2),
+			crate::Error::<Test>::InvalidGuardians
+		);
+		assert_ok!(MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![OSCAR, 4, 5], 2));
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+
+		assert_noop!(
+			MultiAccount::freeze(RuntimeOrigin::signed(BOB), id),
+			crate::Error::<Test>::NotGuardian
+		);
+		assert_ok!(MultiAccount::freeze(RuntimeOrigin::signed(OSCAR), id));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::AccountFrozen { id, guardian: OSCAR }.into(),
+		);
+		assert_noop!(
+			MultiAccount::approve_or_dispatch_call(
+				RuntimeOrigin::signed(BOB),
+				id,
+				Box::new(remark.clone())
+			),
+			crate::Error::<Test>::AccountFrozen
+		);
+		assert_noop!(
+			MultiAccount::account_create_call(RuntimeOrigin::signed(BOB), id, Box::new(remark)),
+			crate::Error::<Test>::AccountFrozen
+		);
+
+		assert_ok!(MultiAccount::unfreeze(RuntimeOrigin::signed(OSCAR), id));
+		assert_noop!(
+			MultiAccount::unfreeze(RuntimeOrigin::signed(OSCAR), id),
+			crate::Error::<Test>::AlreadyVoted
+		);
+		assert!(MultiAccount::is_frozen(&id));
+		// the vote of a guardian that was removed no longer counts
+		assert_ok!(MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![4, 5], 2));
+		assert_ok!(MultiAccount::unfreeze(RuntimeOrigin::signed(5), id));
+		assert!(MultiAccount::is_frozen(&id));
+		assert_ok!(MultiAccount::unfreeze(RuntimeOrigin::signed(4), id));
+		frame_system::Pallet::<Test>::assert_last_event(Event::AccountUnfrozen { id }.into());
+		assert!(!MultiAccount::is_frozen(&id));
+	})
+}
+
+#[test]
+fn a_guardian_should_veto_a_pending_call() {
+	new_test_ext().execute_with(|| {
+		ProposalDeposit::set(5);
+		Balances::force_set_balance(RuntimeOrigin::root(), ALICE, 1_000)
+			.expect("Balance should have been set successfully");
+		let id = registered(2);
+		assert_ok!(MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![OSCAR], 1));
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark)
+		));
+
+		assert_noop!(
+			MultiAccount::veto(RuntimeOrigin::signed(BOB), id, hash),
+			crate::Error::<Test>::NotGuardian
+		);
+		assert_ok!(MultiAccount::veto(RuntimeOrigin::signed(OSCAR), id, hash));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::ProposalVetoed { id, hash, guardian: OSCAR }.into(),
+		);
+		assert!(MultiAccount::get_call(id, hash).is_empty());
+		assert_eq!(MultiAccount::proposal(id, hash), None);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+	})
+}
+
+#[test]
+fn heirs_should_take_over_an_account_that_stays_inactive_through_the_challenge() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		assert_noop!(
+			MultiAccount::set_inactivity_rule(RuntimeOrigin::signed(id), 10, 5, vec![OSCAR], 2),
+			crate::Error::<Test>::InvalidInactivityRule
+		);
+		// heirs have to pass the validator like any signatory
+		Unverified::set(vec![4]);
+		assert_noop!(
+			MultiAccount::set_inactivity_rule(RuntimeOrigin::signed(id), 10, 5, vec![OSCAR, 4], 1),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_ok!(MultiAccount::set_inactivity_rule(
+			RuntimeOrigin::signed(id),
+			10,
+			5,
+			vec![OSCAR, 4],
+			1
+		));
+		assert_ok!(MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![5], 1));
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+
+		assert_noop!(
+			MultiAccount::claim_inheritance(RuntimeOrigin::signed(OSCAR), id),
+			crate::Error::<Test>::AccountStillActive
+		);
+		run_to_block(11);
+		assert_noop!(
+			MultiAccount::claim_inheritance(RuntimeOrigin::signed(BOB), id),
+			crate::Error::<Test>::NotHeir
+		);
+		assert_ok!(MultiAccount::claim_inheritance(RuntimeOrigin::signed(OSCAR), id));
+
+		// a signatory showing up cancels the claim
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark)
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::InheritanceClaimCancelled { id }.into(),
+		);
+		assert_eq!(MultiAccount::inheritance_claim(id), None);
+
+		run_to_block(21);
+		assert_ok!(MultiAccount::claim_inheritance(RuntimeOrigin::signed(4), id));
+		assert_noop!(
+			MultiAccount::finalize_inheritance(RuntimeOrigin::signed(OSCAR), id),
+			crate::Error::<Test>::ChallengePeriodNotOver
+		);
+		run_to_block(26);
+		// a frozen account can't be taken over
+		assert_ok!(MultiAccount::freeze(RuntimeOrigin::signed(5), id));
+		assert_noop!(
+			MultiAccount::finalize_inheritance(RuntimeOrigin::signed(OSCAR), id),
+			crate::Error::<Test>::AccountFrozen
+		);
+		assert_ok!(MultiAccount::unfreeze(RuntimeOrigin::signed(5), id));
+		// and still pass it when they take over
+		Unverified::set(vec![4]);
+		assert_noop!(
+			MultiAccount::finalize_inheritance(RuntimeOrigin::signed(OSCAR), id),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_ok!(MultiAccount::finalize_inheritance(RuntimeOrigin::signed(OSCAR), id));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::InheritanceFinalized { id, signatories: vec![4, OSCAR], threshold: 1 }.into(),
+		);
+		frame_system::Pallet::<Test>::assert_has_event(Event::ProposalRemoved { id, hash }.into());
+		assert_eq!(MultiAccount::get_account(id).into_inner(), vec![4, OSCAR]);
+		assert_eq!(MultiAccount::get_threshold(id), 1);
+		assert!(MultiAccount::get_call(id, hash).is_empty());
+		assert_eq!(MultiAccount::inactivity_rule(id), None);
+		assert_eq!(MultiAccount::guardians(id), None);
+	})
+}
+
+#[test]
+fn a_delegate_should_approve_once_in_the_signers_name_until_the_delegation_expires() {
+	use frame_support::dispatch::GetDispatchInfo;
+	use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+	new_test_ext().execute_with(|| {
+		let other_signatories = vec![BOB, CHARLIE];
+		let threshold = 3;
+		let id = MultiAccount::multi_account_id(other_signatories.as_slice(), threshold);
+		assert_ok!(MultiAccount::register_account(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			other_signatories,
+			threshold,
+		));
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+
+		assert_noop!(
+			MultiAccount::delegate_vote(RuntimeOrigin::signed(BOB), id, BOB, 5),
+			crate::Error::<Test>::InvalidDelegation
+		);
+		assert_ok!(MultiAccount::delegate_vote(RuntimeOrigin::signed(BOB), id, OSCAR, 5));
+		assert_noop!(
+			MultiAccount::approve_as_delegate(
+				RuntimeOrigin::signed(OSCAR),
+				id,
+				CHARLIE,
+				Box::new(remark.clone())
+			),
+			crate::Error::<Test>::NotDelegate
+		);
+		let post = MultiAccount::approve_as_delegate(
+			RuntimeOrigin::signed(OSCAR),
+			id,
+			BOB,
+			Box::new(remark.clone()),
+		)
+		.expect("OSCAR is BOB's delegate");
+		// the call wasn't dispatched, so only the approval itself is charged
+		assert_eq!(post.actual_weight, Some(Weight::from_parts(10_000, 0)));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::DelegatedApproval { id, signer: BOB, delegate: OSCAR, hash }.into(),
+		);
+		assert_eq!(MultiAccount::get_call(id, hash).into_inner(), vec![ALICE, BOB]);
+		// the signer's own vote is already counted
+		assert_noop!(
+			MultiAccount::approve_or_dispatch_call(
+				RuntimeOrigin::signed(BOB),
+				id,
+				Box::new(remark.clone())
+			),
+			crate::Error::<Test>::SenderInSignatories
+		);
+
+		assert_ok!(MultiAccount::delegate_vote(RuntimeOrigin::signed(CHARLIE), id, OSCAR, 5));
+		run_to_block(6);
+		assert_noop!(
+			MultiAccount::approve_as_delegate(
+				RuntimeOrigin::signed(OSCAR),
+				id,
+				CHARLIE,
+				Box::new(remark.clone())
+			),
+			crate::Error::<Test>::DelegationExpired
+		);
+		// and the transaction pool already keeps it out
+		let approval = RuntimeCall::MultiAccount(crate::Call::approve_as_delegate {
+			id,
+			signer: CHARLIE,
+			call: Box::new(remark.clone()),
+		});
+		// the call it might dispatch is charged up front
+		assert_eq!(
+			approval.get_dispatch_info().weight,
+			remark.get_dispatch_info().weight + Weight::from_parts(10_000, 0)
+		);
+		assert_eq!(
+			CheckMultiAccountApproval::<Test>::new().validate(
+				&OSCAR,
+				&approval,
+				&approval.get_dispatch_info(),
+				100
+			),
+			Err(InvalidTransaction::Custom(crate::extensions::NOT_A_DELEGATE).into())
+		);
+		assert_ok!(MultiAccount::revoke_delegation(RuntimeOrigin::signed(CHARLIE), id));
+		assert_eq!(MultiAccount::delegation(id, CHARLIE), None);
+		assert_noop!(
+			MultiAccount::revoke_delegation(RuntimeOrigin::signed(CHARLIE), id),
+			crate::Error::<Test>::NoDelegation
+		);
+
+		// an expired delegation is removed once the signer approves a call itself
+		assert_ok!(MultiAccount::delegate_vote(RuntimeOrigin::signed(CHARLIE), id, OSCAR, 7));
+		run_to_block(8);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark)
+		));
+		assert_eq!(MultiAccount::delegation(id, CHARLIE), None);
+	})
+}
+
+#[test]
+fn the_proposer_should_set_metadata_until_a_second_approval() {
+	new_test_ext().execute_with(|| {
+		Balances::force_set_balance(RuntimeOrigin::root(), ALICE, 1_000)
+			.expect("Balance should have been set successfully");
+		let id = registered(3);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+
+		assert_noop!(
+			MultiAccount::set_proposal_metadata(
+				RuntimeOrigin::signed(BOB),
+				id,
+				hash,
+				b"pay rent".to_vec(),
+				None,
+				vec![]
+			),
+			crate::Error::<Test>::NotProposer
+		);
+		assert_noop!(
+			MultiAccount::set_proposal_metadata(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				hash,
+				b"pay rent".to_vec(),
+				None,
+				vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]
+			),
+			crate::Error::<Test>::MetadataTooLong
+		);
+		assert_ok!(MultiAccount::set_proposal_metadata(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			hash,
+			b"pay rent".to_vec(),
+			Some([7; 32]),
+			vec![b"rent".to_vec()]
+		));
+		let (metadata, deposit) =
+			MultiAccount::proposal_metadata(id, hash).expect("metadata should have been set");
+		assert_eq!(deposit, metadata.encoded_size() as u64);
+		assert_eq!(Balances::reserved_balance(&ALICE), deposit);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark.clone())
+		));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::Approval { id, signer: BOB, hash, metadata: Some(metadata) }.into(),
+		);
+		assert_noop!(
+			MultiAccount::set_proposal_metadata(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				hash,
+				b"pay more rent".to_vec(),
+				None,
+				vec![]
+			),
+			crate::Error::<Test>::MetadataLocked
+		);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark)
+		));
+		assert_eq!(MultiAccount::proposal_metadata(id, hash), None);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+	})
+}
+
+#[test]
+fn a_commitment_should_only_dispatch_when_revealed_before_its_deadline() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		let salt = [9; 32];
+		let commitment = (&remark, salt).using_encoded(sp_io::hashing::blake2_256);
+
+		assert_noop!(
+			MultiAccount::account_create_commitment(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				commitment,
+				1
+			),
+			crate::Error::<Test>::InvalidDeadline
+		);
+		assert_ok!(MultiAccount::account_create_commitment(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			commitment,
+			5
+		));
+		assert_noop!(
+			MultiAccount::reveal_and_dispatch(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark.clone()),
+				salt
+			),
+			crate::Error::<Test>::NotEnoughApprovals
+		);
+		assert_ok!(MultiAccount::approve_commitment(RuntimeOrigin::signed(BOB), id, commitment));
+		assert_noop!(
+			MultiAccount::reveal_and_dispatch(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark.clone()),
+				[0; 32]
+			),
+			crate::Error::<Test>::CommitmentNotFound
+		);
+		assert_noop!(
+			MultiAccount::expire_commitment(RuntimeOrigin::signed(OSCAR), id, commitment),
+			crate::Error::<Test>::CommitmentNotExpired
+		);
+		Unverified::set(vec![CHARLIE]);
+		assert_noop!(
+			MultiAccount::reveal_and_dispatch(
+				RuntimeOrigin::signed(CHARLIE),
+				id,
+				Box::new(remark.clone()),
+				salt
+			),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_ok!(MultiAccount::reveal_and_dispatch(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark.clone()),
+			salt
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::CommitmentRevealed { id, commitment, hash }.into(),
+		);
+		assert_eq!(MultiAccount::commitment_deadline(id, commitment), None);
+
+		// a commitment nobody reveals expires
+		let salt = [8; 32];
+		let commitment = (&remark, salt).using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_commitment(
+			RuntimeOrigin::signed(BOB),
+			id,
+			commitment,
+			5
+		));
+		assert_ok!(MultiAccount::approve_commitment(RuntimeOrigin::signed(ALICE), id, commitment));
+		run_to_block(6);
+		assert_noop!(
+			MultiAccount::reveal_and_dispatch(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark),
+				salt
+			),
+			crate::Error::<Test>::CommitmentExpired
+		);
+		assert_ok!(MultiAccount::expire_commitment(RuntimeOrigin::signed(OSCAR), id, commitment));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::CommitmentExpired { id, commitment }.into(),
+		);
+		assert!(MultiAccount::get_call(id, commitment).is_empty());
+	})
+}
+
+#[test]
+fn a_call_should_wait_for_its_prerequisite_and_block_window() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let fund = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let fund_hash = fund.using_encoded(sp_io::hashing::blake2_256);
+		let spend = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![2] });
+		let spend_hash = spend.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(fund.clone())
+		));
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(spend.clone())
+		));
+		assert_noop!(
+			MultiAccount::set_proposal_conditions(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				spend_hash,
+				None,
+				Some(5),
+				Some(4)
+			),
+			crate::Error::<Test>::InvalidConditions
+		);
+		assert_ok!(MultiAccount::set_proposal_conditions(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			spend_hash,
+			Some(fund_hash),
+			Some(3),
+			Some(10)
+		));
+
+		// the final approval is recorded but the call waits for its prerequisite
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(spend.clone())
+		));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::DispatchDeferred {
+				id,
+				hash: spend_hash,
+				reason: Blocker::PrerequisiteNotExecuted(fund_hash),
+			}
+			.into(),
+		);
+		assert_eq!(MultiAccount::executed(id, spend_hash), None);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(fund)
+		));
+		assert!(MultiAccount::succeeded(&id, &fund_hash));
+		assert_eq!(MultiAccount::blocked_by(&id, &spend_hash), Some(Blocker::TooEarly(3)));
+		assert_noop!(
+			MultiAccount::execute_deferred(
+				RuntimeOrigin::signed(CHARLIE),
+				id,
+				Box::new(spend.clone())
+			),
+			crate::Error::<Test>::ConditionsNotMet
+		);
+
+		run_to_block(3);
+		Unverified::set(vec![CHARLIE]);
+		assert_noop!(
+			MultiAccount::execute_deferred(
+				RuntimeOrigin::signed(CHARLIE),
+				id,
+				Box::new(spend.clone())
+			),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_ok!(MultiAccount::execute_deferred(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(spend)
+		));
+		assert_eq!(MultiAccount::executed(id, spend_hash).map(|receipt| receipt.at), Some(3));
+		assert_eq!(MultiAccount::proposal(id, spend_hash), None);
+		assert_eq!(MultiAccount::proposal_conditions(id, spend_hash), None);
+	})
+}
+
+#[test]
+fn a_call_past_its_window_should_expire_and_old_receipts_should_be_removable() {
+	new_test_ext().execute_with(|| {
+		ReceiptDeposit::set(5);
+		let id = registered(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		let late = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let late_hash = late.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(late.clone())
+		));
+		assert_ok!(MultiAccount::set_proposal_conditions(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			late_hash,
+			None,
+			None,
+			Some(3)
+		));
+		assert_noop!(
+			MultiAccount::expire_proposal(RuntimeOrigin::signed(OSCAR), id, late_hash),
+			crate::Error::<Test>::ProposalNotExpired
+		);
+
+		// once the window closed the call can't be approved anymore, only removed
+		run_to_block(4);
+		assert_noop!(
+			MultiAccount::approve_or_dispatch_call(RuntimeOrigin::signed(BOB), id, Box::new(late)),
+			crate::Error::<Test>::ProposalWindowClosed
+		);
+		assert_ok!(MultiAccount::expire_proposal(RuntimeOrigin::signed(OSCAR), id, late_hash));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::ProposalExpired { id, hash: late_hash }.into(),
+		);
+		assert_eq!(MultiAccount::proposal(id, late_hash), None);
+		assert_eq!(MultiAccount::pending_proposals(id), 0);
+
+		// a dispatched call leaves a receipt the multi-account pays a deposit for
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![2] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark.clone())
+		));
+		assert!(MultiAccount::succeeded(&id, &hash));
+		assert_eq!(Balances::reserved_balance(&id), 5);
+		assert_noop!(
+			MultiAccount::remove_receipt(RuntimeOrigin::signed(OSCAR), id, hash),
+			crate::Error::<Test>::ReceiptStillNeeded
+		);
+		run_to_block(15);
+		assert_ok!(MultiAccount::remove_receipt(RuntimeOrigin::signed(OSCAR), id, hash));
+		assert_eq!(Balances::reserved_balance(&id), 0);
+		assert_eq!(MultiAccount::executed(id, hash), None);
+		// and the call can be proposed again
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark)
+		));
+	})
+}
+
+#[test]
+fn pending_proposals_should_be_limited_per_account_and_per_signatory() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		assert_ok!(MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![OSCAR], 1));
+		let remark = |byte: u8| {
+			RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![byte] })
+		};
+		for byte in 0..3 {
+			assert_ok!(MultiAccount::account_create_call(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark(byte))
+			));
+		}
+		assert_noop!(
+			MultiAccount::account_create_call(RuntimeOrigin::signed(ALICE), id, Box::new(remark(3))),
+			crate::Error::<Test>::TooManyPendingForSignatory
+		);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark(3))
+		));
+		assert_noop!(
+			MultiAccount::account_create_call(RuntimeOrigin::signed(BOB), id, Box::new(remark(4))),
+			crate::Error::<Test>::TooManyPendingForAccount
+		);
+		assert_eq!(MultiAccount::pending_proposals(id), 4);
+		assert_eq!(MultiAccount::pending_by_signatory(id, ALICE), 3);
+
+		// executing and vetoing free up room
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark(0))
+		));
+		let hash = remark(1).using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::veto(RuntimeOrigin::signed(OSCAR), id, hash));
+		assert_eq!(MultiAccount::pending_proposals(id), 2);
+		assert_eq!(MultiAccount::pending_by_signatory(id, ALICE), 1);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark(4))
+		));
+	})
+}
+
+#[test]
+fn proposals_should_be_cancellable_by_their_proposer_and_expire_after_their_lifetime() {
+	new_test_ext().execute_with(|| {
+		ProposalDeposit::set(10);
+		let id = registered(3);
+		let remark = |byte: u8| {
+			RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![byte] })
+		};
+		for byte in 0..2 {
+			assert_ok!(MultiAccount::account_create_call(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark(byte))
+			));
+		}
+		assert_eq!(Balances::reserved_balance(&ALICE), 20);
+
+		// only the proposer can cancel, even after others approved
+		let hash = remark(0).using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark(0))
+		));
+		assert_noop!(
+			MultiAccount::cancel_proposal(RuntimeOrigin::signed(BOB), id, hash),
+			crate::Error::<Test>::NotProposer
+		);
+		assert_ok!(MultiAccount::cancel_proposal(RuntimeOrigin::signed(ALICE), id, hash));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::ProposalCancelled { id, hash }.into(),
+		);
+		assert_eq!(MultiAccount::proposal(id, hash), None);
+		assert!(MultiAccount::get_call(id, hash).is_empty());
+		assert_eq!(MultiAccount::pending_proposals(id), 1);
+		assert_eq!(MultiAccount::pending_by_signatory(id, ALICE), 1);
+		assert_eq!(Balances::reserved_balance(&ALICE), 10);
+
+		// anyone can remove a call that outlived `ProposalLifetime`
+		let hash = remark(1).using_encoded(sp_io::hashing::blake2_256);
+		run_to_block(21);
+		assert_noop!(
+			MultiAccount::expire_proposal(RuntimeOrigin::signed(OSCAR), id, hash),
+			crate::Error::<Test>::ProposalNotExpired
+		);
+		run_to_block(22);
+		assert_ok!(MultiAccount::expire_proposal(RuntimeOrigin::signed(OSCAR), id, hash));
+		assert_eq!(MultiAccount::pending_proposals(id), 0);
+		assert_eq!(MultiAccount::pending_by_signatory(id, ALICE), 0);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+	})
+}
+
+#[test]
+fn runtime_upgrade_should_count_existing_proposals_once() {
+	use frame_support::traits::{GetStorageVersion, Hooks, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		let id = registered(3);
+		let remark = |byte: u8| {
+			RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![byte] })
+		};
+		for (signer, byte) in [(ALICE, 0), (ALICE, 1), (BOB, 2)] {
+			assert_ok!(MultiAccount::account_create_call(
+				RuntimeOrigin::signed(signer),
+				id,
+				Box::new(remark(byte))
+			));
+		}
+		// as if the proposals were made before the counters existed
+		let hash = remark(0).using_encoded(sp_io::hashing::blake2_256);
+		crate::PendingProposals::<Test>::remove(id);
+		crate::PendingBySignatory::<Test>::remove(id, ALICE);
+		crate::PendingBySignatory::<Test>::remove(id, BOB);
+		crate::ProposedAt::<Test>::remove(id, hash);
+		StorageVersion::new(0).put::<MultiAccount>();
+
+		run_to_block(5);
+		MultiAccount::on_runtime_upgrade();
+		assert_eq!(MultiAccount::on_chain_storage_version(), 1);
+		assert_eq!(MultiAccount::pending_proposals(id), 3);
+		assert_eq!(MultiAccount::pending_by_signatory(id, ALICE), 2);
+		assert_eq!(MultiAccount::pending_by_signatory(id, BOB), 1);
+		assert_eq!(MultiAccount::proposed_at(id, hash), Some(5));
+		let other = remark(1).using_encoded(sp_io::hashing::blake2_256);
+		assert_eq!(MultiAccount::proposed_at(id, other), Some(1));
+
+		// a second upgrade leaves the counters alone
+		MultiAccount::on_runtime_upgrade();
+		assert_eq!(MultiAccount::pending_proposals(id), 3);
+	})
+}
+
+#[test]
+fn approve_many_should_apply_each_approval_on_its_own() {
+	new_test_ext().execute_with(|| {
+		let first = registered(2);
+		let second = registered(3);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		for id in [first, second] {
+			assert_ok!(MultiAccount::account_create_call(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark.clone())
+			));
+		}
+
+		assert_noop!(
+			MultiAccount::approve_many(
+				RuntimeOrigin::signed(BOB),
+				vec![(first, hash); 4],
+				Weight::from_parts(1_000_000, 0)
+			),
+			crate::Error::<Test>::BatchTooLarge
+		);
+		assert_ok!(MultiAccount::approve_many(
+			RuntimeOrigin::signed(BOB),
+			vec![(first, hash), (first, [0; 32]), (second, hash)],
+			Weight::from_parts(1_000_000_000, 0)
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::BatchApproval { index: 0, id: first, hash, result: Ok(()) }.into(),
+		);
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::BatchApproval {
+				index: 1,
+				id: first,
+				hash: [0; 32],
+				result: Err(crate::Error::<Test>::PreimageNotFound.into()),
+			}
+			.into(),
+		);
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::BatchApproval { index: 2, id: second, hash, result: Ok(()) }.into(),
+		);
+		assert!(MultiAccount::succeeded(&first, &hash));
+		assert_eq!(MultiAccount::get_call(second, hash).into_inner(), vec![ALICE, BOB]);
+
+		// the final approval doesn't fit in the weight limit and is rolled back
+		assert_ok!(MultiAccount::approve_many(
+			RuntimeOrigin::signed(CHARLIE),
+			vec![(second, hash)],
+			Weight::zero()
+		));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::BatchApproval {
+				index: 0,
+				id: second,
+				hash,
+				result: Err(crate::Error::<Test>::BatchWeightExhausted.into()),
+			}
+			.into(),
+		);
+		assert_eq!(MultiAccount::get_call(second, hash).into_inner(), vec![ALICE, BOB]);
+	})
+}
+
+#[test]
+fn approve_many_should_only_charge_for_the_calls_it_dispatched() {
+	use frame_support::{dispatch::GetDispatchInfo, traits::Get};
+
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let remark = |byte: u8| {
+			RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![byte] })
+		};
+		let deferred = remark(0).using_encoded(sp_io::hashing::blake2_256);
+		let dispatched = remark(1).using_encoded(sp_io::hashing::blake2_256);
+		for byte in 0..2 {
+			assert_ok!(MultiAccount::account_create_call(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				Box::new(remark(byte))
+			));
+		}
+		assert_ok!(MultiAccount::set_proposal_conditions(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			deferred,
+			None,
+			Some(5),
+			None
+		));
+
+		let post = MultiAccount::approve_many(
+			RuntimeOrigin::signed(BOB),
+			vec![(id, deferred), (id, dispatched)],
+			Weight::from_parts(1_000_000_000, 0),
+		)
+		.expect("Both approvals should have been applied");
+		assert!(!MultiAccount::succeeded(&id, &deferred));
+		assert!(MultiAccount::succeeded(&id, &dispatched));
+		// the deferred call adds nothing on top of the base weight of the two approvals
+		let base = (Weight::from_parts(10_000, 0) +
+			<Test as frame_system::Config>::DbWeight::get().reads_writes(7, 6))
+		.saturating_mul(2);
+		assert_eq!(post.actual_weight, Some(base + remark(1).get_dispatch_info().weight));
+	})
+}
+
+#[test]
+fn a_sub_account_should_hold_its_own_funds_and_block_closing() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let payroll = MultiAccount::sub_account_id(&id, 1);
+		assert_ne!(payroll, id);
+		assert_ne!(payroll, MultiAccount::sub_account_id(&id, 2));
+		Balances::force_set_balance(RuntimeOrigin::root(), payroll, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+
+		let pay = RuntimeCall::Balances(pallet_balances::Call::<Test>::transfer_keep_alive {
+			dest: OSCAR,
+			value: 50_000,
+		});
+		assert_ok!(MultiAccount::as_sub_account(RuntimeOrigin::signed(id), 1, Box::new(pay)));
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::SubAccountCall { id, index: 1, sub_account: payroll }.into(),
+		);
+		assert_eq!(Balances::free_balance(&OSCAR), 50_000);
+		assert_eq!(Balances::free_balance(&payroll), FREE_BALANCE - 50_000);
+		assert_eq!(MultiAccount::sub_account(id, 1), Some(payroll));
+
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR),
+			crate::Error::<Test>::SubAccountHasFunds
+		);
+		assert_noop!(
+			MultiAccount::as_sub_account(
+				RuntimeOrigin::signed(id),
+				4,
+				Box::new(RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![] }))
+			),
+			crate::Error::<Test>::InvalidSubAccountIndex
+		);
+	})
+}
+
+#[test]
+fn a_funded_sub_account_should_block_closing_before_it_is_used() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let unused = MultiAccount::sub_account_id(&id, 3);
+		Balances::force_set_balance(RuntimeOrigin::root(), unused, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		assert_eq!(MultiAccount::sub_account(id, 3), None);
+		assert_noop!(
+			MultiAccount::close_account(RuntimeOrigin::signed(id), OSCAR),
+			crate::Error::<Test>::SubAccountHasFunds
+		);
+	})
+}
+
+#[test]
+fn a_failing_call_should_keep_its_approvals_until_it_runs_out_of_attempts() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		// the multi-account has no funds yet
+		let pay = RuntimeCall::Balances(pallet_balances::Call::<Test>::transfer_keep_alive {
+			dest: OSCAR,
+			value: 50_000,
+		});
+		let hash = pay.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(pay.clone())
+		));
+		assert_noop!(
+			MultiAccount::retry_execution(RuntimeOrigin::signed(ALICE), id, Box::new(pay.clone())),
+			crate::Error::<Test>::NotRetryable
+		);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(pay.clone())
+		));
+		let receipt = MultiAccount::executed(id, hash).expect("the attempt should be recorded");
+		assert_eq!(receipt.attempts, 1);
+		assert!(receipt.result.is_err());
+		assert_eq!(MultiAccount::get_call(id, hash).into_inner(), vec![ALICE, BOB]);
+		assert!(MultiAccount::proposal(id, hash).is_some());
+
+		// the second attempt succeeds after the account was funded
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		assert_ok!(MultiAccount::retry_execution(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(pay)
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::CallExecuted { id, hash, attempt: 2, result: Ok(()) }.into(),
+		);
+		assert!(MultiAccount::succeeded(&id, &hash));
+		assert_eq!(MultiAccount::proposal(id, hash), None);
+		assert_eq!(Balances::free_balance(&OSCAR), 50_000);
+	})
+}
+
+#[test]
+fn a_call_that_ran_out_of_attempts_should_lose_its_approvals() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let pay = RuntimeCall::Balances(pallet_balances::Call::<Test>::transfer_keep_alive {
+			dest: OSCAR,
+			value: 50_000,
+		});
+		let hash = pay.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(pay.clone())
+		));
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(pay.clone())
+		));
+		assert_ok!(MultiAccount::retry_execution(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(pay.clone())
+		));
+		let receipt = MultiAccount::executed(id, hash).expect("the attempts should be recorded");
+		assert_eq!(receipt.attempts, 2);
+		assert!(receipt.result.is_err());
+		assert_eq!(MultiAccount::proposal(id, hash), None);
+		assert!(MultiAccount::get_call(id, hash).is_empty());
+		assert_eq!(MultiAccount::proposed_at(id, hash), None);
+		assert_eq!(MultiAccount::pending_proposals(id), 0);
+
+		// so the call can be proposed again, with attempts of its own
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(pay.clone())
+		));
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(pay)
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::CallExecuted { id, hash, attempt: 1, result: Ok(()) }.into(),
+		);
+		assert!(MultiAccount::succeeded(&id, &hash));
+	})
+}
+
+#[test]
+fn a_revealed_call_that_fails_should_be_recorded_and_revealable_again() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let pay = RuntimeCall::Balances(pallet_balances::Call::<Test>::transfer_keep_alive {
+			dest: OSCAR,
+			value: 50_000,
+		});
+		let salt = [9; 32];
+		let commitment = (&pay, salt).using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_commitment(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			commitment,
+			5
+		));
+		assert_ok!(MultiAccount::approve_commitment(RuntimeOrigin::signed(BOB), id, commitment));
+
+		// the multi-account has no funds yet, which doesn't fail the reveal
+		assert_ok!(MultiAccount::reveal_and_dispatch(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(pay.clone()),
+			salt
+		));
+		let receipt =
+			MultiAccount::executed(id, commitment).expect("the attempt should be recorded");
+		assert_eq!(receipt.attempts, 1);
+		assert!(receipt.result.is_err());
+		assert_eq!(MultiAccount::commitment_deadline(id, commitment), Some(5));
+
+		Balances::force_set_balance(RuntimeOrigin::root(), id, FREE_BALANCE.into())
+			.expect("Balance should have been set successfully");
+		assert_ok!(MultiAccount::reveal_and_dispatch(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(pay),
+			salt
+		));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::CallExecuted { id, hash: commitment, attempt: 2, result: Ok(()) }.into(),
+		);
+		assert!(MultiAccount::succeeded(&id, &commitment));
+		assert_eq!(MultiAccount::commitment_deadline(id, commitment), None);
+		assert_eq!(Balances::free_balance(&OSCAR), 50_000);
+	})
+}
+
+#[test]
+fn only_verified_signatories_should_register_and_approve() {
+	new_test_ext().execute_with(|| {
+		let other_signatories = vec![BOB, CHARLIE];
+		let threshold = 2;
+		let id = MultiAccount::multi_account_id(other_signatories.as_slice(), threshold);
+		Unverified::set(vec![CHARLIE]);
+		assert_noop!(
+			MultiAccount::register_account(
+				RuntimeOrigin::signed(ALICE),
+				id,
+				other_signatories.clone(),
+				threshold,
+			),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_eq!(registered(threshold), id);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+
+		// a signatory that lost its verification can't approve anymore
+		Unverified::set(vec![BOB]);
+		assert_noop!(
+			MultiAccount::approve_or_dispatch_call(
+				RuntimeOrigin::signed(BOB),
+				id,
+				Box::new(remark.clone())
+			),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark)
+		));
+	})
+}
+
+#[test]
+fn a_lost_signatory_key_should_be_replaced_after_enough_votes_and_the_delay() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark)
+		));
+		assert_noop!(
+			MultiAccount::initiate_recovery(RuntimeOrigin::signed(ALICE), id, CHARLIE, BOB),
+			crate::Error::<Test>::InvalidRecovery
+		);
+
+		// the key was not lost after all
+		assert_ok!(MultiAccount::initiate_recovery(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			CHARLIE,
+			OSCAR
+		));
+		assert_ok!(MultiAccount::cancel_recovery(RuntimeOrigin::signed(CHARLIE), id));
+		assert_eq!(MultiAccount::recovery(id, CHARLIE), None);
+
+		assert_ok!(MultiAccount::initiate_recovery(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			CHARLIE,
+			OSCAR
+		));
+		assert_noop!(
+			MultiAccount::finalize_recovery(RuntimeOrigin::signed(OSCAR), id, CHARLIE),
+			crate::Error::<Test>::NotEnoughRecoveryVotes
+		);
+		assert_ok!(MultiAccount::vote_recovery(RuntimeOrigin::signed(BOB), id, CHARLIE));
+		assert_noop!(
+			MultiAccount::vote_recovery(RuntimeOrigin::signed(BOB), id, CHARLIE),
+			crate::Error::<Test>::AlreadyVoted
+		);
+		assert_noop!(
+			MultiAccount::finalize_recovery(RuntimeOrigin::signed(OSCAR), id, CHARLIE),
+			crate::Error::<Test>::RecoveryDelayNotOver
+		);
+
+		run_to_block(6);
+		assert_ok!(MultiAccount::finalize_recovery(RuntimeOrigin::signed(OSCAR), id, CHARLIE));
+		assert_eq!(MultiAccount::get_account(id).into_inner(), vec![ALICE, BOB, OSCAR]);
+		// the new key took over the approval of the lost one
+		assert_eq!(MultiAccount::get_call(id, hash).into_inner(), vec![OSCAR]);
+		assert_eq!(MultiAccount::recovery(id, CHARLIE), None);
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::SignatoryRecovered { id, lost: CHARLIE, new: OSCAR }.into(),
+		);
+	})
+}
+
+#[test]
+fn a_recovery_should_respect_freezes_and_verification_and_leave_dispatched_calls_alone() {
+	new_test_ext().execute_with(|| {
+		let id = registered(2);
+		let new_key = 4000;
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(remark.clone())
+		));
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark)
+		));
+		assert!(MultiAccount::succeeded(&id, &hash));
+
+		assert_ok!(MultiAccount::set_guardians(RuntimeOrigin::signed(id), vec![OSCAR], 1));
+		assert_ok!(MultiAccount::freeze(RuntimeOrigin::signed(OSCAR), id));
+		assert_noop!(
+			MultiAccount::initiate_recovery(RuntimeOrigin::signed(ALICE), id, CHARLIE, new_key),
+			crate::Error::<Test>::AccountFrozen
+		);
+		assert_ok!(MultiAccount::unfreeze(RuntimeOrigin::signed(OSCAR), id));
+
+		Unverified::set(vec![ALICE]);
+		assert_noop!(
+			MultiAccount::initiate_recovery(RuntimeOrigin::signed(ALICE), id, CHARLIE, new_key),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_ok!(MultiAccount::initiate_recovery(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			CHARLIE,
+			new_key
+		));
+		Unverified::set(vec![BOB]);
+		assert_noop!(
+			MultiAccount::vote_recovery(RuntimeOrigin::signed(BOB), id, CHARLIE),
+			crate::Error::<Test>::SignatoryNotVerified
+		);
+		Unverified::set(vec![]);
+		assert_ok!(MultiAccount::vote_recovery(RuntimeOrigin::signed(BOB), id, CHARLIE));
+
+		run_to_block(6);
+		assert_ok!(MultiAccount::freeze(RuntimeOrigin::signed(OSCAR), id));
+		assert_noop!(
+			MultiAccount::finalize_recovery(RuntimeOrigin::signed(OSCAR), id, CHARLIE),
+			crate::Error::<Test>::AccountFrozen
+		);
+		assert_ok!(MultiAccount::unfreeze(RuntimeOrigin::signed(OSCAR), id));
+		assert_ok!(MultiAccount::finalize_recovery(RuntimeOrigin::signed(OSCAR), id, CHARLIE));
+		assert_eq!(MultiAccount::get_account(id).into_inner(), vec![ALICE, BOB, new_key]);
+		// the record of who approved the dispatched call is kept
+		assert_eq!(MultiAccount::get_call(id, hash).into_inner(), vec![ALICE, CHARLIE]);
+	})
+}
+
+#[test]
+fn a_stuck_call_should_need_fewer_approvals_as_the_threshold_decays() {
+	new_test_ext().execute_with(|| {
+		let id = registered(3);
+		assert_noop!(
+			MultiAccount::set_threshold_decay(
+				RuntimeOrigin::signed(id),
+				Some(ThresholdDecay { decay_after: 10, decay_step: 5, floor: 4 })
+			),
+			crate::Error::<Test>::InvalidThresholdDecay
+		);
+		assert_ok!(MultiAccount::set_threshold_decay(
+			RuntimeOrigin::signed(id),
+			Some(ThresholdDecay { decay_after: 10, decay_step: 5, floor: 2 })
+		));
+		let first = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let first_hash = first.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(first.clone())
+		));
+		assert_eq!(MultiAccount::effective_threshold(&id, &first_hash), 3);
+
+		// after `decay_after` blocks the call needs one approval less
+		run_to_block(11);
+		assert_eq!(MultiAccount::effective_threshold(&id, &first_hash), 2);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(first.clone())
+		));
+		assert!(MultiAccount::succeeded(&id, &first_hash));
+		// the dispatched call still counts as approved
+		assert_noop!(
+			MultiAccount::approve_or_dispatch_call(
+				RuntimeOrigin::signed(CHARLIE),
+				id,
+				Box::new(first)
+			),
+			crate::Error::<Test>::DispatchHasAlreadyOccured
+		);
+
+		// a call whose approvals meet the lowered threshold is dispatched on request
+		let second = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![2] });
+		let second_hash = second.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(second.clone())
+		));
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(second.clone())
+		));
+		assert_noop!(
+			MultiAccount::execute_deferred(
+				RuntimeOrigin::signed(CHARLIE),
+				id,
+				Box::new(second.clone())
+			),
+			crate::Error::<Test>::NotEnoughApprovals
+		);
+		// the floor holds however long the call is stuck
+		run_to_block(40);
+		assert_eq!(MultiAccount::effective_threshold(&id, &second_hash), 2);
+		assert_ok!(MultiAccount::execute_deferred(
+			RuntimeOrigin::signed(CHARLIE),
+			id,
+			Box::new(second)
+		));
+		assert!(MultiAccount::succeeded(&id, &second_hash));
+	})
+}
+
+#[test]
+fn a_call_dispatched_with_a_decayed_threshold_should_stay_dispatched_when_the_decay_is_off() {
+	use frame_support::dispatch::GetDispatchInfo;
+	use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+	new_test_ext().execute_with(|| {
+		let id = registered(3);
+		assert_ok!(MultiAccount::set_threshold_decay(
+			RuntimeOrigin::signed(id),
+			Some(ThresholdDecay { decay_after: 10, decay_step: 5, floor: 2 })
+		));
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		let hash = remark.using_encoded(sp_io::hashing::blake2_256);
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+		run_to_block(11);
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark.clone())
+		));
+		assert!(MultiAccount::succeeded(&id, &hash));
+
+		// without the decay the call needs three approvals again, but a third one must not
+		// dispatch it a second time
+		for decay in [None, Some(ThresholdDecay { decay_after: 100, decay_step: 5, floor: 2 })] {
+			assert_ok!(MultiAccount::set_threshold_decay(RuntimeOrigin::signed(id), decay));
+			assert_eq!(MultiAccount::effective_threshold(&id, &hash), 3);
+			let approval = RuntimeCall::MultiAccount(crate::Call::approve_or_dispatch_call {
+				id,
+				call: Box::new(remark.clone()),
+			});
+			assert_eq!(
+				CheckMultiAccountApproval::<Test>::new().validate(
+					&CHARLIE,
+					&approval,
+					&approval.get_dispatch_info(),
+					100
+				),
+				Err(InvalidTransaction::Stale.into())
+			);
+			assert_noop!(
+				MultiAccount::approve_or_dispatch_call(
+					RuntimeOrigin::signed(CHARLIE),
+					id,
+					Box::new(remark.clone())
+				),
+				crate::Error::<Test>::DispatchHasAlreadyOccured
+			);
+		}
+		assert_eq!(MultiAccount::executed(id, hash).map(|receipt| receipt.attempts), Some(1));
+	})
+}
+
+#[test]
+fn a_stored_preimage_should_be_paid_for_by_the_proposer() {
+	new_test_ext().execute_with(|| {
+		PreimageDepositPerByte::set(2);
+		Balances::force_set_balance(RuntimeOrigin::root(), ALICE, 1_000)
+			.expect("Balance should have been set successfully");
+		let id = registered(2);
+		let remark = RuntimeCall::System(frame_system::Call::<Test>::remark { remark: vec![1] });
+		assert_ok!(MultiAccount::account_create_call(
+			RuntimeOrigin::signed(ALICE),
+			id,
+			Box::new(remark.clone())
+		));
+		assert_eq!(Balances::reserved_balance(&ALICE), 2 * remark.encoded_size() as u64);
+
+		assert_ok!(MultiAccount::approve_or_dispatch_call(
+			RuntimeOrigin::signed(BOB),
+			id,
+			Box::new(remark)
+		));
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+	})
+}
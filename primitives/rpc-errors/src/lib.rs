@@ -0,0 +1,110 @@
+//! JSON-RPC errors shared by the pallet RPCs.
+//!
+//! Every failure gets its own error code and, where there is something useful to say, a typed
+//! `data` payload, so clients can tell a missing block from an unknown account without parsing
+//! messages. Code `1` is kept for errors raised while calling into the runtime, which is what
+//! every error used to be reported as.
+
+use jsonrpsee::{
+	core::Error as JsonRpseeError,
+	types::error::{CallError, ErrorObject},
+};
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Calling into the runtime failed, e.g. the runtime trapped or the API is not implemented.
+pub const RUNTIME_ERROR: i32 = 1;
+/// The requested block is not known to the node.
+pub const BLOCK_NOT_FOUND: i32 = 2;
+/// The runtime at the requested block implements an older version of the runtime API.
+pub const API_VERSION_MISMATCH: i32 = 3;
+/// The runtime metadata could not be decoded.
+pub const METADATA_ERROR: i32 = 4;
+/// No multi-account is registered under the given id.
+pub const UNKNOWN_ACCOUNT: i32 = 100;
+/// The multi-account has no pending call with the given hash.
+pub const CALL_NOT_FOUND: i32 = 101;
+/// The bytes are not a call of the runtime at the requested block.
+pub const INVALID_CALL: i32 = 102;
+/// The signatories and threshold do not form a valid multi-account.
+pub const INVALID_SIGNATORIES: i32 = 103;
+
+/// An RPC failure, converted into a JSON-RPC error object with its own code.
+#[derive(Debug)]
+pub enum Error {
+	/// Calling into the runtime failed.
+	RuntimeApi(String),
+	/// The requested block is not known to the node.
+	BlockNotFound(String),
+	/// The runtime API is older than the RPC method needs.
+	ApiVersionMismatch { api: &'static str, required: u32, found: u32 },
+	/// The runtime metadata could not be decoded.
+	Metadata(String),
+	/// No multi-account is registered under the given id.
+	UnknownAccount,
+	/// The multi-account has no pending call with the given hash.
+	CallNotFound,
+	/// The bytes are not a call of the runtime, with the reason if one is known.
+	InvalidCall(Option<String>),
+	/// The signatories and threshold do not form a valid multi-account.
+	InvalidSignatories,
+}
+
+impl Error {
+	/// Wrap any error raised by the runtime API call itself.
+	pub fn runtime_api(err: impl std::fmt::Debug) -> Self {
+		Self::RuntimeApi(format!("{:?}", err))
+	}
+}
+
+/// `data` of an `API_VERSION_MISMATCH` error.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionMismatch {
+	api: &'static str,
+	required: u32,
+	found: u32,
+}
+
+fn error_object(code: i32, message: &str, data: Option<impl Serialize>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(code, message, data)).into()
+}
+
+impl From<Error> for JsonRpseeError {
+	fn from(err: Error) -> Self {
+		match err {
+			Error::RuntimeApi(reason) => error_object(RUNTIME_ERROR, "Runtime error", Some(reason)),
+			Error::BlockNotFound(hash) => error_object(BLOCK_NOT_FOUND, "Block not found", Some(hash)),
+			Error::ApiVersionMismatch { api, required, found } => error_object(
+				API_VERSION_MISMATCH,
+				"Runtime API version mismatch",
+				Some(VersionMismatch { api, required, found }),
+			),
+			Error::Metadata(reason) =>
+				error_object(METADATA_ERROR, "Invalid runtime metadata", Some(reason)),
+			Error::UnknownAccount =>
+				error_object(UNKNOWN_ACCOUNT, "Unknown multi-account", None::<()>),
+			Error::CallNotFound => error_object(CALL_NOT_FOUND, "Call not found", None::<()>),
+			Error::InvalidCall(reason) => error_object(INVALID_CALL, "Invalid call", reason),
+			Error::InvalidSignatories =>
+				error_object(INVALID_SIGNATORIES, "Invalid signatories or threshold", None::<()>),
+		}
+	}
+}
+
+/// Resolve the block an RPC call should run at, defaulting to the best block, and make sure the
+/// node knows about it.
+pub fn block_hash<Block, C>(client: &C, at: Option<Block::Hash>) -> Result<Block::Hash, Error>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	// If the block hash is not supplied assume the best block.
+	let hash = at.unwrap_or_else(|| client.info().best_hash);
+	match client.header(hash) {
+		Ok(Some(_)) => Ok(hash),
+		Ok(None) => Err(Error::BlockNotFound(format!("{:?}", hash))),
+		Err(err) => Err(Error::runtime_api(err)),
+	}
+}
@@ -6,7 +6,9 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::{Decode, Encode};
 use multi_account;
+pub use multi_account::{CheckMultiAccountApproval, SponsorApprovalFees};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -28,7 +30,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
+		StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -105,7 +108,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -290,6 +293,24 @@ impl pallet_connect::Config for Runtime {
 	type MinimumLockableAmount = MinimumLockableAmount;
 	type MaxNameLength = ConstU32<10>;
 	type Randomness = InsecureRandomness;
+	type Locks = BalanceLocks;
+}
+
+/// Reads the locks Connect places on registration from the balances pallet.
+pub struct BalanceLocks;
+impl pallet_connect::InspectLocks<AccountId, Balance> for BalanceLocks {
+	fn locked(id: frame_support::traits::LockIdentifier, who: &AccountId) -> Balance {
+		Balances::locks(who).iter().find(|lock| lock.id == id).map_or(0, |lock| lock.amount)
+	}
+}
+
+/// Only registered Connect users that hold the minimum lock can be signatories of a
+/// multi-account.
+pub struct ConnectUsers;
+impl multi_account::SignatoryValidator<AccountId> for ConnectUsers {
+	fn is_valid(who: &AccountId) -> bool {
+		Connect::is_verified(who)
+	}
 }
 
 /// configure the multi-account pallet for the runtime
@@ -298,6 +319,31 @@ impl multi_account::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type MaxSignatories = MaxSignatories;
 	type RuntimeCall = RuntimeCall;
+	type MaxCallSize = ConstU32<{ 16 * 1024 }>;
+	type Currency = Balances;
+	// added to the fee based priority, so a final approval only goes ahead of transactions that
+	// paid the same tip
+	type ApprovalPriorityBoost = ConstU64<1_000>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type ScheduleDeposit = ConstU128<{ EXISTENTIAL_DEPOSIT }>;
+	type MaxSchedulesPerAccount = ConstU32<16>;
+	type RegistrationDeposit = ConstU128<{ 10 * EXISTENTIAL_DEPOSIT }>;
+	type ProposalDeposit = ConstU128<{ 2 * EXISTENTIAL_DEPOSIT }>;
+	type PreimageDepositPerByte = ConstU128<{ EXISTENTIAL_DEPOSIT / 10 }>;
+	type MaxTitleLength = ConstU32<128>;
+	type MaxTags = ConstU32<8>;
+	type MaxTagLength = ConstU32<32>;
+	type MetadataDepositPerByte = ConstU128<{ EXISTENTIAL_DEPOSIT / 10 }>;
+	type MaxPendingPerAccount = ConstU32<64>;
+	type MaxPendingPerSignatory = ConstU32<16>;
+	type ProposalLifetime = ConstU32<{ 90 * DAYS }>;
+	type MaxApprovalBatch = ConstU32<32>;
+	type MaxSubAccounts = ConstU16<16>;
+	type MaxExecutionAttempts = ConstU32<3>;
+	type ReceiptDeposit = ConstU128<{ EXISTENTIAL_DEPOSIT }>;
+	type ReceiptLifetime = ConstU32<{ 30 * DAYS }>;
+	type RecoveryDelay = ConstU32<{ 7 * DAYS }>;
+	type SignatoryValidator = ConnectUsers;
 }
 // configure the pallet-contracts for the runtime
 impl pallet_contracts::Config for Runtime {
@@ -368,7 +414,9 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	// charges fees like `ChargeTransactionPayment`, but lets multi-accounts pay for approvals
+	multi_account::SponsorApprovalFees<Runtime>,
+	multi_account::CheckMultiAccountApproval<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -400,49 +448,237 @@ mod benches {
 	);
 }
 type CallHash = [u8; 32];
+use multi_runtime_api::MultiAccountApiError;
+
+/// The threshold of a registered multi-account. Registration never allows a threshold of zero,
+/// so a zero threshold means there is no account.
+fn registered_account(id: &AccountId) -> Result<u32, MultiAccountApiError> {
+	match MultiAccount::get_threshold(id) {
+		0 => Err(MultiAccountApiError::UnknownAccount),
+		threshold => Ok(threshold as u32),
+	}
+}
+
+/// The approvals of a call the multi-account has pending.
+fn pending_call(id: AccountId, call_hash: CallHash) -> Result<Vec<AccountId>, MultiAccountApiError> {
+	registered_account(&id)?;
+	let signers = MultiAccount::get_call(id, call_hash).into_inner();
+	if signers.is_empty() {
+		// this means the call does not exist on chain
+		return Err(MultiAccountApiError::CallNotFound)
+	}
+	Ok(signers)
+}
 
 impl_runtime_apis! {
 
 	// this rpc can obviously be written better
 	// should it return an error if a call does not exist or None 
-	impl multi_runtime_api::MultiAccountApi<Block, AccountId> for Runtime {
+	#[api_version(9)]
+	impl multi_runtime_api::MultiAccountApi<Block, AccountId, Balance> for Runtime {
 		/// get the number of accounts that have approved a particular call hash
-        fn get_approvals_for_call(id: AccountId, call_hash: CallHash ) -> Option<u32>{
-			let signatures = MultiAccount::get_call(id, call_hash);
-			let signers = signatures.into_inner();
-			if signers.len() > 0 {
-				Some(signers.len() as u32)
-			}else {
-				// this means the call does not exist on chain
-				None
-			}
-
+        fn get_approvals_for_call(id: AccountId, call_hash: CallHash ) -> Result<u32, MultiAccountApiError>{
+			let signers = pending_call(id, call_hash)?;
+			Ok(signers.len() as u32)
 		}
-        /// get the signatories for an account. Would return an empty vec if account is not a multiAccunt
-        fn get_signatories_for_account(id: AccountId) -> Option<Vec<AccountId>>{
-			let signers = MultiAccount::get_account(id).into_inner();
-			Some(signers)
+        /// get the signatories for an account
+        fn get_signatories_for_account(id: AccountId) -> Result<Vec<AccountId>, MultiAccountApiError>{
+			registered_account(&id)?;
+			Ok(MultiAccount::get_account(id).into_inner())
 		}
         
 		/// get the threshold required for a call to pass
-        fn get_threshold_for_account(id: AccountId) -> Option<u32>{
-			let threshold = MultiAccount::get_threshold(id);
-			if threshold > 0 {
-				Some(threshold as u32)
-			} else {
-				None
-			}
+        fn get_threshold_for_account(id: AccountId) -> Result<u32, MultiAccountApiError>{
+			registered_account(&id)
 		}
         /// get the accounts that has approved a particular call
-        fn get_approval_accounts_for_call(id: AccountId, call_hash: CallHash) -> Option<Vec<AccountId>>{
-			let signatures = MultiAccount::get_call(id, call_hash);
-			let signers = signatures.into_inner();
-			if signers.len() > 0 {
-				Some(signers)
-			}else {
-				// this means the call does not exist on chain
-				None
+        fn get_approval_accounts_for_call(id: AccountId, call_hash: CallHash) -> Result<Vec<AccountId>, MultiAccountApiError>{
+			pending_call(id, call_hash)
+		}
+		/// dispatch a call as the multi-account and throw the resulting state away
+		fn dry_run(id: AccountId, call: Vec<u8>) -> Result<multi_runtime_api::DryRunInfo<Balance>, MultiAccountApiError> {
+			use frame_support::dispatch::{extract_actual_weight, GetDispatchInfo};
+
+			let call = RuntimeCall::decode(&mut &call[..])
+				.map_err(|_| MultiAccountApiError::InvalidCall)?;
+			registered_account(&id)?;
+			let info = call.get_dispatch_info();
+			// the fee is charged to whoever sends the approval that reaches the threshold
+			let approval = RuntimeCall::MultiAccount(multi_account::Call::approve_or_dispatch_call {
+				id: id.clone(),
+				call: Box::new(call.clone()),
+			});
+			let partial_fee =
+				TransactionPayment::query_call_info(approval.clone(), approval.encoded_size() as u32)
+					.partial_fee;
+			let (result, events) = MultiAccount::dry_run(id, call);
+			Ok(multi_runtime_api::DryRunInfo {
+				actual_weight: extract_actual_weight(&result, &info),
+				result: result.map(|_| ()).map_err(|err| err.error),
+				partial_fee,
+				events: events.iter().map(|event| event.encode()).collect(),
+			})
+		}
+		/// sort and validate the signatories before deriving the multi-account id
+		fn derive_account_id(signatories: Vec<AccountId>, threshold: u16) -> Result<AccountId, MultiAccountApiError> {
+			MultiAccount::derive_account_id(signatories, threshold)
+				.map_err(|_| MultiAccountApiError::InvalidSignatories)
+		}
+		/// pick the events of this block that belong to the multi-account
+		fn account_events(id: AccountId) -> Vec<multi_runtime_api::AccountEvent<AccountId>> {
+			use multi_account::Event;
+			use multi_runtime_api::AccountEvent;
+
+			System::read_events_no_consensus()
+				.filter_map(|record| match record.event {
+					RuntimeEvent::MultiAccount(Event::Proposal { id: account, proposer, hash })
+						if account == id =>
+						Some(AccountEvent::Proposed { proposer, call_hash: hash }),
+					RuntimeEvent::MultiAccount(Event::Approval { id: account, signer, hash, .. })
+						if account == id =>
+						Some(AccountEvent::Approved { signer, call_hash: hash }),
+					RuntimeEvent::MultiAccount(Event::CallExecuted { id: account, hash, result, .. })
+						if account == id =>
+						Some(AccountEvent::Executed { call_hash: hash, result }),
+					RuntimeEvent::MultiAccount(Event::CommitmentExpired { id: account, commitment })
+						if account == id =>
+						Some(AccountEvent::Expired { call_hash: commitment }),
+					RuntimeEvent::MultiAccount(Event::ProposalExpired { id: account, hash })
+						if account == id =>
+						Some(AccountEvent::Expired { call_hash: hash }),
+					RuntimeEvent::MultiAccount(
+						Event::ProposalCancelled { id: account, hash } |
+						Event::ProposalVetoed { id: account, hash, .. } |
+						Event::ProposalStale { id: account, hash } |
+						Event::ProposalRemoved { id: account, hash },
+					) if account == id => Some(AccountEvent::Removed { call_hash: hash }),
+					_ => None,
+				})
+				.collect()
+		}
+		/// the preimage is only kept while the call is pending
+		fn get_call_data(id: AccountId, call_hash: CallHash) -> Result<Option<Vec<u8>>, MultiAccountApiError> {
+			pending_call(id.clone(), call_hash)?;
+			Ok(MultiAccount::get_call_data(id, call_hash).map(|data| data.into_inner()))
+		}
+		/// bundle what a UI needs to show an account, so it takes one round trip
+		fn account_info(id: AccountId) -> Option<multi_runtime_api::AccountView<AccountId, Balance, BlockNumber>> {
+			let threshold = registered_account(&id).ok()?;
+			Some(multi_runtime_api::AccountView {
+				signatories: MultiAccount::get_account(&id).into_inner(),
+				threshold,
+				balance: Balances::free_balance(&id),
+				nonce: System::account_nonce(&id),
+				pending_proposals: MultiAccount::pending_call_count(&id),
+				policy: multi_runtime_api::AccountPolicy {
+					guardians: MultiAccount::guardians(&id)
+						.map_or(0, |set| set.guardians.len() as u32),
+					frozen: MultiAccount::unfreeze_votes(&id).is_some(),
+					fee_budget: MultiAccount::fee_budget(&id),
+					inactivity_period: MultiAccount::inactivity_rule(&id)
+						.map(|rule| rule.inactivity_period),
+					decay_after: MultiAccount::threshold_decay(&id).map(|decay| decay.decay_after),
+				},
+			})
+		}
+		/// the recurring transfers of the account, in the order they were scheduled
+		fn schedules(id: AccountId) -> Vec<multi_runtime_api::ScheduleView<AccountId, Balance, BlockNumber>> {
+			let mut schedules = multi_account::Schedules::<Runtime>::iter_prefix(&id)
+				.map(|(index, schedule)| multi_runtime_api::ScheduleView {
+					index,
+					dest: schedule.dest,
+					amount: schedule.amount,
+					interval: schedule.interval,
+					remaining: schedule.remaining,
+					next: schedule.next,
+				})
+				.collect::<Vec<_>>();
+			schedules.sort_by_key(|schedule| schedule.index);
+			schedules
+		}
+		/// join the proposals with their approvals and metadata
+		fn pending_proposals(id: AccountId) -> Vec<multi_runtime_api::ProposalView<AccountId>> {
+			multi_account::Proposals::<Runtime>::iter_prefix(&id)
+				.map(|(hash, proposal)| {
+					let metadata = MultiAccount::proposal_metadata(&id, hash).map(|(metadata, _)| metadata);
+					multi_runtime_api::ProposalView {
+						call_hash: hash,
+						proposer: proposal.proposer,
+						approvals: MultiAccount::get_call(&id, hash).into_inner(),
+						stale: MultiAccount::is_stale(&id, &hash),
+						title: metadata.as_ref().map(|metadata| metadata.title.to_vec()).unwrap_or_default(),
+						description_hash: metadata.as_ref().and_then(|metadata| metadata.description_hash),
+						tags: metadata
+							.map(|metadata| metadata.tags.into_iter().map(|tag| tag.into_inner()).collect())
+							.unwrap_or_default(),
+					}
+				})
+				.collect()
+		}
+		/// only proposals that were not dispatched yet can be blocked
+		fn blocked_reason(id: AccountId, call_hash: CallHash) -> Result<Option<multi_runtime_api::BlockedReason<BlockNumber>>, MultiAccountApiError> {
+			use multi_account::Blocker;
+			use multi_runtime_api::BlockedReason;
+
+			registered_account(&id)?;
+			if MultiAccount::proposal(&id, call_hash).is_none() {
+				return Err(MultiAccountApiError::CallNotFound)
 			}
+			Ok(MultiAccount::blocked_by(&id, &call_hash).map(|blocker| match blocker {
+				Blocker::PrerequisiteNotExecuted(prerequisite) =>
+					BlockedReason::PrerequisiteNotExecuted { prerequisite },
+				Blocker::TooEarly(not_before) => BlockedReason::TooEarly { not_before },
+				Blocker::TooLate(not_after) => BlockedReason::TooLate { not_after },
+			}))
+		}
+		/// every index below `MaxSubAccounts` that was used or holds funds, since funds can be
+		/// sent to a sub-account before it is used
+		fn sub_accounts(id: AccountId) -> Vec<multi_runtime_api::SubAccountView<AccountId, Balance>> {
+			use frame_support::traits::{Currency, Get};
+
+			(0..<Runtime as multi_account::Config>::MaxSubAccounts::get())
+				.filter_map(|index| {
+					let address = MultiAccount::sub_account_id(&id, index);
+					let used = multi_account::SubAccounts::<Runtime>::contains_key(&id, index);
+					(used || Balances::total_balance(&address) != 0).then(|| {
+						multi_runtime_api::SubAccountView {
+							index,
+							balance: Balances::free_balance(&address),
+							address,
+						}
+					})
+				})
+				.collect()
+		}
+		/// look up every signatory in Connect
+		fn signatories(id: AccountId) -> Result<Vec<multi_runtime_api::SignatoryView<AccountId>>, MultiAccountApiError> {
+			registered_account(&id)?;
+			Ok(MultiAccount::get_account(&id)
+				.into_iter()
+				.map(|account| multi_runtime_api::SignatoryView {
+					name: Connect::name_of(&account),
+					verified: Connect::is_verified(&account),
+					account,
+				})
+				.collect())
+		}
+		/// every recovery needs the same number of votes
+		fn recoveries(id: AccountId) -> Vec<multi_runtime_api::RecoveryView<AccountId, BlockNumber>> {
+			let votes_needed = MultiAccount::recovery_threshold(&id);
+			multi_account::Recoveries::<Runtime>::iter_prefix(&id)
+				.map(|(lost, recovery)| multi_runtime_api::RecoveryView {
+					lost,
+					new: recovery.new,
+					votes: recovery.votes.into_inner(),
+					votes_needed,
+					delay_end: recovery.delay_end,
+				})
+				.collect()
+		}
+		/// equals the threshold of the account unless it opted into threshold decay
+		fn effective_threshold(id: AccountId, call_hash: CallHash) -> Result<u32, MultiAccountApiError> {
+			pending_call(id.clone(), call_hash)?;
+			Ok(MultiAccount::effective_threshold(&id, &call_hash) as u32)
 		}
 	}
 